@@ -0,0 +1,336 @@
+//! Per-kernel benchmarks over a grid of log sizes and input distributions.
+//!
+//! The grid defaults to log sizes 16,20 and the random and small distributions; override it with
+//! `KERNEL_LOG_SIZES=14-22` and `KERNEL_DISTS=sequential,random,small,sparse`. The SIMD kernels
+//! pick chunk sizes from this machine's profile, if it has one; set `SUMCHECK_SIMD_PROFILE=` to
+//! bench the heuristic ones instead.
+
+use ark_bn254::Fr;
+use ark_std::UniformRand;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use poly_bind_bench::bench::{parse_list, parse_usize_list, Distribution};
+use poly_bind_bench::field::binary::{Packed, TowerField, B1, B128};
+use poly_bind_bench::field::goldilocks::{Goldilocks, GoldilocksExt2};
+use poly_bind_bench::field::kernels::Isa;
+use poly_bind_bench::field::lazy::{inner_product_wide, LazySum};
+use poly_bind_bench::field::m31::{M31, QM31};
+use poly_bind_bench::field::small::{MulBase, SmallField};
+use poly_bind_bench::poly::plain::{eq_evals, rand_vec, DensePolynomial};
+use poly_bind_bench::poly::sparse::SparsePolynomial;
+use poly_bind_bench::poly::structured::EqPolynomial;
+use poly_bind_bench::poly::tensor::TensorPolynomial;
+use poly_bind_bench::sumcheck::binary::BinarySumcheck;
+use poly_bind_bench::sumcheck::plain::PlainSumcheck;
+use poly_bind_bench::sumcheck::scratch::ScratchArena;
+use poly_bind_bench::sumcheck::simd::{SIMDPolynomial, SIMDSumcheck};
+use poly_bind_bench::sumcheck::small_field::SmallFieldSumcheck;
+use poly_bind_bench::sumcheck::sparse::SparseSumcheck;
+use poly_bind_bench::sumcheck::tensor::TensorSumcheck;
+use poly_bind_bench::sumcheck::tuning::{self, Kernel};
+use poly_bind_bench::sumcheck::{cubic_claim, Backend, CubicSumcheck, CubicSumcheckProof};
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+fn dists() -> Vec<Distribution> {
+    parse_list(&env_or("KERNEL_DISTS", "random,small")).unwrap()
+}
+
+fn grid() -> Vec<(usize, Distribution)> {
+    let log_sizes = parse_usize_list(&env_or("KERNEL_LOG_SIZES", "16,20")).unwrap();
+    let dists = dists();
+    log_sizes
+        .iter()
+        .flat_map(|&log_size| dists.iter().map(move |&dist| (log_size, dist)))
+        .collect()
+}
+
+fn id(function: &str, log_size: usize, dist: Distribution) -> BenchmarkId {
+    BenchmarkId::new(function, format!("{log_size}/{dist}"))
+}
+
+/// A challenge drawn independently of the bound polynomial.
+fn challenge() -> Fr {
+    Distribution::Random.sample(0, 7)[0]
+}
+
+fn bind(c: &mut Criterion) {
+    let r = challenge();
+    let mut group = c.benchmark_group("bind");
+    for (log_size, dist) in grid() {
+        let evals = dist.sample(log_size, 0);
+        let dense = || DensePolynomial::new(evals.clone());
+
+        group.bench_function(id("bound_poly_var_top", log_size, dist), |bench| {
+            bench.iter_batched(
+                dense,
+                |mut p| p.bound_poly_var_top(&r),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(id("bound_poly_var_top_par", log_size, dist), |bench| {
+            bench.iter_batched(
+                dense,
+                |mut p| p.bound_poly_var_top_par(&r),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(id("bound_poly_var_bot", log_size, dist), |bench| {
+            bench.iter_batched(
+                dense,
+                |mut p| p.bound_poly_var_bot(&r),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(id("simd_bound_poly_var_top_par", log_size, dist), |bench| {
+            bench.iter_batched(
+                || SIMDPolynomial { Z: evals.clone() },
+                |mut p| p.bound_poly_var_top_par(&r),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn eval_cubic_top(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval_cubic_top");
+    for (log_size, dist) in grid() {
+        let (eq, a, b) = dist.inputs(log_size);
+
+        let mut plain = PlainSumcheck::new(eq.clone(), a.clone(), b.clone());
+        group.bench_function(id("plain", log_size, dist), |bench| {
+            bench.iter(|| plain.eval_cubic_top())
+        });
+        let mut simd = SIMDSumcheck::new(eq, a, b);
+        group.bench_function(id("simd", log_size, dist), |bench| {
+            bench.iter(|| simd.eval_cubic_top())
+        });
+    }
+    group.finish();
+}
+
+/// The eval kernel with its scratch buffers reused across calls, as in a sumcheck, against a
+/// fresh arena every call, which allocates each thread's buffers again.
+fn eval_scratch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval_scratch");
+    for (log_size, dist) in grid() {
+        let (eq, a, b) = dist.inputs(log_size);
+        let simd = SIMDSumcheck::new(eq, a, b);
+        let chunk_size = tuning::chunk_size(Kernel::Eval, 1 << (log_size - 1));
+
+        group.bench_function(id("reused", log_size, dist), |bench| {
+            bench.iter(|| simd.eval_cubic_top_chunked(chunk_size))
+        });
+        group.bench_function(id("fresh", log_size, dist), |bench| {
+            bench.iter(|| simd.eval_cubic_top_with(chunk_size, &ScratchArena::new()))
+        });
+    }
+    group.finish();
+}
+
+/// The inner products of the eval kernel, reducing every product against one reduction per sum.
+/// The gap grows with size; `KERNEL_LOG_SIZES=20-28` covers the range the sumcheck runs at.
+fn inner_product(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inner_product");
+    for (log_size, dist) in grid() {
+        let a = dist.sample(log_size, 0);
+        let b = dist.sample(log_size, 1);
+
+        group.bench_function(id("reduced", log_size, dist), |bench| {
+            bench.iter(|| vectorized_fields::inner_product_bn254(&a, &b))
+        });
+        group.bench_function(id("lazy", log_size, dist), |bench| {
+            bench.iter(|| inner_product_wide(&a, &b).reduce())
+        });
+    }
+    group.finish();
+}
+
+/// The in-crate BN254 kernels on every instruction set this CPU supports, next to
+/// vectorized-fields.
+fn field_kernels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("field_kernels");
+    for (log_size, dist) in grid() {
+        let a = dist.sample(log_size, 0);
+        let b = dist.sample(log_size, 1);
+        let mut out = vec![Fr::from(0u64); a.len()];
+
+        group.bench_function(id("mul/vectorized-fields", log_size, dist), |bench| {
+            bench.iter(|| vectorized_fields::mul_vec_bn254(&a, &b, &mut out))
+        });
+        group.bench_function(id("add/vectorized-fields", log_size, dist), |bench| {
+            bench.iter(|| vectorized_fields::add_vec_bn254(&a, &b, &mut out))
+        });
+        for isa in Isa::supported() {
+            group.bench_function(id(&format!("mul/{isa}"), log_size, dist), |bench| {
+                bench.iter(|| isa.mul_vec(&a, &b, &mut out))
+            });
+            group.bench_function(id(&format!("add/{isa}"), log_size, dist), |bench| {
+                bench.iter(|| isa.add_vec(&a, &b, &mut out))
+            });
+            group.bench_function(id(&format!("sub/{isa}"), log_size, dist), |bench| {
+                bench.iter(|| isa.sub_vec(&a, &b, &mut out))
+            });
+            group.bench_function(
+                id(&format!("inner_product/{isa}"), log_size, dist),
+                |bench| bench.iter(|| isa.inner_product(&a, &b).reduce()),
+            );
+        }
+    }
+    group.finish();
+}
+
+/// The first round of the small-field sumcheck on packed base-field values, against the scalar
+/// base-field round and the same values embedded in the extension.
+fn small_field(c: &mut Criterion) {
+    fn bench_pair<B, E>(c: &mut Criterion, base: &str, ext: &str)
+    where
+        B: SmallField,
+        E: LazySum + MulBase<BasePrimeField = B>,
+    {
+        let mut group = c.benchmark_group("small_field");
+        for log_size in parse_usize_list(&env_or("KERNEL_LOG_SIZES", "16,20")).unwrap() {
+            let evals = rand_vec::<B>(1 << log_size);
+            let lifted: Vec<E> = evals.iter().map(|x| E::from_base_prime_field(*x)).collect();
+
+            let mut prover =
+                SmallFieldSumcheck::<B, E>::from_base(evals.clone(), evals.clone(), evals.clone());
+            group.bench_function(BenchmarkId::new(base, log_size), |bench| {
+                bench.iter(|| prover.eval_cubic_top())
+            });
+            let mut scalar = PlainSumcheck::new(evals.clone(), evals.clone(), evals.clone());
+            group.bench_function(
+                BenchmarkId::new(format!("{base}-scalar"), log_size),
+                |bench| bench.iter(|| scalar.eval_cubic_top()),
+            );
+            let mut prover =
+                SmallFieldSumcheck::<B, E>::new(lifted.clone(), lifted.clone(), lifted.clone());
+            group.bench_function(BenchmarkId::new(ext, log_size), |bench| {
+                bench.iter(|| prover.eval_cubic_top())
+            });
+        }
+        group.finish();
+    }
+
+    bench_pair::<Goldilocks, GoldilocksExt2>(c, "eval/goldilocks", "eval/goldilocks-ext2");
+    bench_pair::<M31, QM31>(c, "eval/m31", "eval/qm31");
+}
+
+/// The first round of the GF(2^128) sumcheck on bit witnesses, packed against lifted.
+fn binary(c: &mut Criterion) {
+    let mut group = c.benchmark_group("binary");
+    let mut rng = ark_std::test_rng();
+    for log_size in parse_usize_list(&env_or("KERNEL_LOG_SIZES", "16,20")).unwrap() {
+        let eq: Vec<B128> = (0..1 << log_size).map(|_| B128::rand(&mut rng)).collect();
+        let bits: Vec<B1> = (0..1 << log_size).map(|_| B1::rand(&mut rng)).collect();
+        let packed = Packed::from_slice(&bits);
+        let lifted: Vec<B128> = bits.iter().map(|x| B128::from_subfield(*x)).collect();
+        let lifted = Packed::from_slice(&lifted);
+
+        let prover = BinarySumcheck::new(eq.clone(), packed.clone(), packed);
+        group.bench_function(BenchmarkId::new("eval/packed-bits", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+        let prover = BinarySumcheck::new(eq, lifted.clone(), lifted);
+        group.bench_function(BenchmarkId::new("eval/b128", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+    }
+    group.finish();
+}
+
+/// The first round on a and b with one entry in 64 non-zero, sparse against dense.
+fn sparse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse");
+    for log_size in parse_usize_list(&env_or("KERNEL_LOG_SIZES", "16,20")).unwrap() {
+        let point = rand_vec::<Fr>(log_size);
+        let values = rand_vec::<Fr>(1 << log_size);
+        let sparse = SparsePolynomial::new(
+            log_size,
+            (0..1 << log_size)
+                .step_by(64)
+                .map(|i| (i, values[i]))
+                .collect(),
+        );
+
+        let mut prover = SparseSumcheck::from_sparse(
+            &EqPolynomial::new(point.clone()),
+            sparse.clone(),
+            sparse.clone(),
+        );
+        group.bench_function(BenchmarkId::new("eval/sparse", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+        let dense = sparse.to_dense().Z;
+        let mut prover = PlainSumcheck::new(eq_evals(&point), dense.clone(), dense);
+        group.bench_function(BenchmarkId::new("eval/dense", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+    }
+    group.finish();
+}
+
+/// The first round with eq kept as a tensor of its point against eq written out.
+fn tensor(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tensor");
+    for log_size in parse_usize_list(&env_or("KERNEL_LOG_SIZES", "16,20")).unwrap() {
+        let point = rand_vec::<Fr>(log_size);
+        let (a, b) = (rand_vec::<Fr>(1 << log_size), rand_vec::<Fr>(1 << log_size));
+
+        let mut prover =
+            TensorSumcheck::from_tensor(TensorPolynomial::eq(&point), a.clone(), b.clone());
+        group.bench_function(BenchmarkId::new("eval/tensor", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+        let mut prover = PlainSumcheck::new(eq_evals(&point), a, b);
+        group.bench_function(BenchmarkId::new("eval/dense", log_size), |bench| {
+            bench.iter(|| prover.eval_cubic_top())
+        });
+    }
+    group.finish();
+}
+
+fn eval_uni(c: &mut Criterion) {
+    let r = challenge();
+    let mut group = c.benchmark_group("eval_uni");
+    for dist in dists() {
+        let evals = dist.sample(2, 0);
+        let round_poly = (evals[0], evals[1], evals[2], evals[3]);
+        group.bench_function(BenchmarkId::from_parameter(dist), |bench| {
+            bench.iter(|| CubicSumcheckProof::eval_uni(round_poly, &r))
+        });
+    }
+    group.finish();
+}
+
+fn verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+    for (log_size, dist) in grid() {
+        let (eq, a, b) = dist.inputs(log_size);
+        let claim = cubic_claim(&eq, &a, &b);
+        let proof = Backend::Simd.prove(eq, a, b);
+        group.bench_function(
+            BenchmarkId::from_parameter(format!("{log_size}/{dist}")),
+            |bench| bench.iter(|| proof.verify(&claim).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    kernels,
+    bind,
+    eval_cubic_top,
+    eval_scratch,
+    inner_product,
+    field_kernels,
+    small_field,
+    binary,
+    sparse,
+    tensor,
+    eval_uni,
+    verify
+);
+criterion_main!(kernels);
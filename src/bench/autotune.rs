@@ -0,0 +1,228 @@
+use super::Distribution;
+use crate::machine::MachineInfo;
+use crate::sumcheck::simd::{SIMDPolynomial, SIMDSumcheck};
+use crate::sumcheck::tuning::{Kernel, KernelTuning, SimdProfile};
+use crate::sumcheck::CubicSumcheck;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Elements processed per timing sample; small sizes repeat the kernel to reach it.
+const SAMPLE_ELEMENTS: usize = 1 << 16;
+
+#[derive(Clone, Debug)]
+pub struct TuneConfig {
+    pub threads: Vec<usize>,
+    /// Polynomial log sizes; each is tuned at its half-length, the size the kernels split into.
+    pub log_sizes: Vec<usize>,
+    pub reps: usize,
+}
+
+/// The best chunk size for one kernel, thread count and size class, next to the heuristic's.
+#[derive(Clone, Debug, Serialize)]
+pub struct TunePoint {
+    pub kernel: &'static str,
+    pub threads: usize,
+    pub log_half: u32,
+    pub best_chunk: usize,
+    pub best_ns: u64,
+    pub heuristic_chunk: usize,
+    pub heuristic_ns: u64,
+}
+
+impl TunePoint {
+    pub fn speedup(&self) -> f64 {
+        self.heuristic_ns as f64 / self.best_ns.max(1) as f64
+    }
+}
+
+/// Powers of two from 16 below `n`, the heuristic's choice and `n` itself (sequential). Chunks
+/// longer than `n` behave like `n`, so the heuristic is clamped to it.
+fn candidates(kernel: Kernel, n: usize, threads: usize) -> Vec<usize> {
+    let mut chunks: Vec<usize> = (4..)
+        .map(|i| 1 << i)
+        .take_while(|&c| c < n && c <= 1 << 13)
+        .chain([kernel.heuristic_chunk_size(n, threads).min(n), n])
+        .collect();
+    chunks.sort_unstable();
+    chunks.dedup();
+    chunks
+}
+
+/// Median over `reps` samples of the per-call time of `kernel` on a half-length of `n`.
+fn time_kernel(kernel: Kernel, n: usize, chunk_size: usize, reps: usize) -> Duration {
+    let inner = (SAMPLE_ELEMENTS / n).max(1);
+    let evals = Distribution::Random.sample(n.ilog2() as usize + 1, 0);
+    let r = Distribution::Random.sample(0, 3)[0];
+
+    let mut samples: Vec<Duration> = match kernel {
+        Kernel::Bind => (0..reps)
+            .map(|_| {
+                let mut polys: Vec<SIMDPolynomial> = (0..inner)
+                    .map(|_| SIMDPolynomial { Z: evals.clone() })
+                    .collect();
+                let start = Instant::now();
+                for poly in &mut polys {
+                    poly.bound_poly_var_top_chunked(&r, chunk_size);
+                }
+                start.elapsed()
+            })
+            .collect(),
+        Kernel::Eval => {
+            let sumcheck = SIMDSumcheck::new(evals.clone(), evals.clone(), evals);
+            (0..reps)
+                .map(|_| {
+                    let start = Instant::now();
+                    for _ in 0..inner {
+                        std::hint::black_box(sumcheck.eval_cubic_top_chunked(chunk_size));
+                    }
+                    start.elapsed()
+                })
+                .collect()
+        }
+    };
+    samples.sort_unstable();
+    samples[samples.len() / 2] / inner as u32
+}
+
+/// Picks the fastest chunk size per size class, then sets the sequential threshold at the
+/// smallest class from which a parallel chunking wins in every larger class.
+fn tune_kernel(kernel: Kernel, config: &TuneConfig, points: &mut Vec<TunePoint>) -> KernelTuning {
+    let threads = rayon::current_num_threads();
+    let mut log_halves: Vec<u32> = config
+        .log_sizes
+        .iter()
+        .filter(|&&log_size| log_size > 0)
+        .map(|&log_size| log_size as u32 - 1)
+        .collect();
+    log_halves.sort_unstable();
+    log_halves.dedup();
+
+    let mut tuning = KernelTuning {
+        threads,
+        ..KernelTuning::default()
+    };
+    let mut sequential_wins = Vec::new();
+    for &log_half in &log_halves {
+        let n = 1 << log_half;
+        let timed: Vec<(usize, Duration)> = candidates(kernel, n, threads)
+            .into_iter()
+            .map(|chunk| (chunk, time_kernel(kernel, n, chunk, config.reps)))
+            .collect();
+        let (best_chunk, best) = *timed.iter().min_by_key(|(_, t)| *t).unwrap();
+        let heuristic_chunk = kernel.heuristic_chunk_size(n, threads).min(n);
+        let heuristic = timed.iter().find(|(c, _)| *c == heuristic_chunk).unwrap().1;
+
+        tuning.chunk_sizes.insert(log_half, best_chunk);
+        sequential_wins.push((log_half, best_chunk == n));
+        points.push(TunePoint {
+            kernel: match kernel {
+                Kernel::Bind => "bind",
+                Kernel::Eval => "eval",
+            },
+            threads,
+            log_half,
+            best_chunk,
+            best_ns: best.as_nanos() as u64,
+            heuristic_chunk,
+            heuristic_ns: heuristic.as_nanos() as u64,
+        });
+    }
+
+    let parallel_from = sequential_wins
+        .iter()
+        .rev()
+        .take_while(|(_, sequential)| !sequential)
+        .last()
+        .map(|(log_half, _)| *log_half);
+    tuning.sequential_below = match parallel_from {
+        // Everything tuned runs sequentially.
+        None => log_halves.last().map_or(0, |&l| 1 << (l + 1)),
+        Some(log_half) if Some(&log_half) == log_halves.first() => 0,
+        Some(log_half) => 1 << log_half,
+    };
+    tuning
+        .chunk_sizes
+        .retain(|&log_half, _| 1 << log_half >= tuning.sequential_below);
+    tuning
+}
+
+pub fn tune(config: &TuneConfig) -> Result<(SimdProfile, Vec<TunePoint>), Box<dyn Error>> {
+    let mut profile = SimdProfile {
+        machine: MachineInfo::current(),
+        bind: Vec::new(),
+        eval: Vec::new(),
+    };
+    let mut points = Vec::new();
+    for &threads in &config.threads {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()?;
+        pool.install(|| {
+            profile
+                .bind
+                .push(tune_kernel(Kernel::Bind, config, &mut points));
+            profile
+                .eval
+                .push(tune_kernel(Kernel::Eval, config, &mut points));
+        });
+    }
+    Ok((profile, points))
+}
+
+pub fn write_table(points: &[TunePoint], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "{:<6} {:>7} {:>8} {:>10} {:>12} {:>10} {:>12} {:>8}",
+        "kernel",
+        "threads",
+        "log half",
+        "best chunk",
+        "best us",
+        "heuristic",
+        "heur. us",
+        "speedup"
+    )?;
+    for p in points {
+        writeln!(
+            out,
+            "{:<6} {:>7} {:>8} {:>10} {:>12.2} {:>10} {:>12.2} {:>7.2}x",
+            p.kernel,
+            p.threads,
+            p.log_half,
+            p.best_chunk,
+            p.best_ns as f64 / 1e3,
+            p.heuristic_chunk,
+            p.heuristic_ns as f64 / 1e3,
+            p.speedup()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tunes_every_size_class() {
+        let config = TuneConfig {
+            threads: vec![1, 2],
+            log_sizes: vec![3, 5, 7],
+            reps: 1,
+        };
+        let (profile, points) = tune(&config).unwrap();
+        assert_eq!(points.len(), 2 * 2 * 3);
+        for tuning in profile.bind.iter().chain(&profile.eval) {
+            for (&log_half, &chunk) in &tuning.chunk_sizes {
+                assert!(1 << log_half >= tuning.sequential_below);
+                assert!(chunk >= 1 && chunk <= 1 << log_half);
+            }
+        }
+        assert_eq!(
+            profile.bind.iter().map(|t| t.threads).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use super::report::{BenchReport, BenchResult, PhaseStats};
+use crate::machine::MachineInfo;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+type PhaseGetter = fn(&BenchResult) -> &PhaseStats;
+
+/// Phases checked for regressions, named after the kernels they time.
+pub const PHASES: &[(&str, PhaseGetter)] = &[
+    ("eval_cubic_top", |r| &r.eval),
+    ("bind_top", |r| &r.bind),
+    ("bound_poly_var_top_par", |r| &r.bound_poly),
+];
+
+/// A saved bench run together with where and on what revision it was recorded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Baseline {
+    pub machine: MachineInfo,
+    pub git_revision: String,
+    pub report: BenchReport,
+}
+
+impl Baseline {
+    pub fn new(report: BenchReport) -> Self {
+        Baseline {
+            machine: MachineInfo::current(),
+            git_revision: git_revision(),
+            report,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let baseline = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let file = std::fs::File::create(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+fn git_revision() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[derive(Clone, Debug)]
+pub struct PhaseComparison {
+    pub backend: String,
+    pub log_size: usize,
+    pub threads: usize,
+    pub distribution: String,
+    pub phase: &'static str,
+    pub baseline_ns: u64,
+    pub current_ns: u64,
+    pub regressed: bool,
+}
+
+impl PhaseComparison {
+    /// current / baseline median.
+    pub fn ratio(&self) -> f64 {
+        self.current_ns as f64 / self.baseline_ns.max(1) as f64
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Comparison {
+    pub phases: Vec<PhaseComparison>,
+    /// Current results with no matching configuration in the baseline.
+    pub unmatched: usize,
+}
+
+impl Comparison {
+    pub fn regressions(&self) -> impl Iterator<Item = &PhaseComparison> {
+        self.phases.iter().filter(|p| p.regressed)
+    }
+
+    pub fn write_table(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{:<11} {:>4} {:>7} {:<10} {:<24} {:>12} {:>12} {:>8}",
+            "backend", "log", "threads", "dist", "phase", "base ms", "current ms", "ratio"
+        )?;
+        for p in &self.phases {
+            writeln!(
+                out,
+                "{:<11} {:>4} {:>7} {:<10} {:<24} {:>12.3} {:>12.3} {:>7.3}x{}",
+                p.backend,
+                p.log_size,
+                p.threads,
+                p.distribution,
+                p.phase,
+                p.baseline_ns as f64 / 1e6,
+                p.current_ns as f64 / 1e6,
+                p.ratio(),
+                if p.regressed { "  REGRESSED" } else { "" }
+            )?;
+        }
+        if self.unmatched > 0 {
+            writeln!(out, "{} configurations not in baseline", self.unmatched)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares median phase times; a phase regresses when it is more than `threshold` (e.g. 0.05 = 5%)
+/// slower than the baseline.
+pub fn compare(baseline: &BenchReport, current: &BenchReport, threshold: f64) -> Comparison {
+    let mut comparison = Comparison::default();
+    for cur in &current.results {
+        let Some(base) = baseline.results.iter().find(|b| {
+            b.backend == cur.backend
+                && b.log_size == cur.log_size
+                && b.threads == cur.threads
+                && b.distribution == cur.distribution
+                && b.shrink == cur.shrink
+        }) else {
+            comparison.unmatched += 1;
+            continue;
+        };
+
+        for (phase, stats) in PHASES {
+            let baseline_ns = stats(base).median_ns;
+            let current_ns = stats(cur).median_ns;
+            comparison.phases.push(PhaseComparison {
+                backend: cur.backend.clone(),
+                log_size: cur.log_size,
+                threads: cur.threads,
+                distribution: cur.distribution.clone(),
+                phase,
+                baseline_ns,
+                current_ns,
+                regressed: current_ns as f64 > baseline_ns as f64 * (1.0 + threshold),
+            });
+        }
+    }
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(backend: &str, eval_ns: u64, bind_ns: u64) -> BenchResult {
+        let stats = |ns| PhaseStats {
+            min_ns: ns,
+            median_ns: ns,
+            p95_ns: ns,
+        };
+        BenchResult {
+            backend: backend.to_string(),
+            log_size: 20,
+            threads: 8,
+            distribution: "random".to_string(),
+            shrink: "keep".to_string(),
+            reps: 5,
+            total: stats(eval_ns + bind_ns),
+            eval: stats(eval_ns),
+            bind: stats(bind_ns),
+            transcript: stats(0),
+            bound_poly: stats(bind_ns / 3),
+            peak_bytes: 0,
+            rounds: vec![],
+        }
+    }
+
+    #[test]
+    fn flags_phases_beyond_threshold() {
+        let baseline = BenchReport {
+            results: vec![result("plain", 1000, 900), result("simd", 1000, 900)],
+        };
+        let current = BenchReport {
+            results: vec![
+                result("plain", 1040, 900),
+                result("simd", 1200, 900),
+                result("gpu", 10, 10),
+            ],
+        };
+
+        let comparison = compare(&baseline, &current, 0.05);
+        assert_eq!(comparison.phases.len(), 2 * PHASES.len());
+        assert_eq!(comparison.unmatched, 1);
+        let regressed: Vec<_> = comparison
+            .regressions()
+            .map(|p| (p.backend.as_str(), p.phase))
+            .collect();
+        assert_eq!(regressed, vec![("simd", "eval_cubic_top")]);
+    }
+
+    #[test]
+    fn baseline_roundtrip() {
+        let path = std::env::temp_dir().join("poly_bind_bench_baseline_roundtrip.json");
+        let baseline = Baseline::new(BenchReport {
+            results: vec![result("simd", 1000, 900)],
+        });
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.machine, baseline.machine);
+        assert_eq!(loaded.git_revision, baseline.git_revision);
+        assert_eq!(loaded.report.results[0].eval.median_ns, 1000);
+    }
+}
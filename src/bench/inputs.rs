@@ -0,0 +1,84 @@
+use ark_bn254::Fr;
+use ark_std::rand::{rngs::StdRng, Rng, SeedableRng};
+use ark_std::{UniformRand, Zero};
+use rayon::prelude::*;
+
+/// Elements generated per seeded rng so generation parallelizes deterministically.
+const GEN_CHUNK: usize = 1 << 14;
+
+/// Roughly one in `SPARSE_INV_DENSITY` entries of a sparse input is non-zero.
+const SPARSE_INV_DENSITY: u32 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// seed * n + i, so eq / a / b differ but stay cheap to generate.
+    Sequential,
+    /// Uniform field elements.
+    Random,
+    /// Uniform u16 values.
+    SmallInt,
+    /// Mostly zero, with uniform field elements at the remaining positions.
+    Sparse,
+}
+
+impl std::str::FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sequential" => Ok(Distribution::Sequential),
+            "random" => Ok(Distribution::Random),
+            "small" => Ok(Distribution::SmallInt),
+            "sparse" => Ok(Distribution::Sparse),
+            _ => Err(format!(
+                "unknown distribution '{s}' (expected sequential, random, small or sparse)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Distribution::Sequential => "sequential",
+            Distribution::Random => "random",
+            Distribution::SmallInt => "small",
+            Distribution::Sparse => "sparse",
+        })
+    }
+}
+
+impl Distribution {
+    pub fn sample(self, log_size: usize, seed: u64) -> Vec<Fr> {
+        let n = 1 << log_size;
+        let mut evals = vec![Fr::zero(); n];
+        evals
+            .par_chunks_mut(GEN_CHUNK)
+            .enumerate()
+            .for_each(|(chunk_index, chunk)| {
+                let offset = chunk_index * GEN_CHUNK;
+                let mut rng = StdRng::seed_from_u64((seed << 32) ^ chunk_index as u64);
+                for (i, eval) in chunk.iter_mut().enumerate() {
+                    *eval = match self {
+                        Distribution::Sequential => Fr::from(seed * n as u64 + (offset + i) as u64),
+                        Distribution::Random => Fr::rand(&mut rng),
+                        Distribution::SmallInt => Fr::from(rng.gen::<u16>()),
+                        Distribution::Sparse if rng.gen_ratio(1, SPARSE_INV_DENSITY) => {
+                            Fr::rand(&mut rng)
+                        }
+                        Distribution::Sparse => Fr::zero(),
+                    };
+                }
+            });
+        evals
+    }
+
+    /// Independent (eq, a, b) inputs of size 2^log_size.
+    pub fn inputs(self, log_size: usize) -> (Vec<Fr>, Vec<Fr>, Vec<Fr>) {
+        (
+            self.sample(log_size, 0),
+            self.sample(log_size, 1),
+            self.sample(log_size, 2),
+        )
+    }
+}
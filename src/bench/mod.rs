@@ -0,0 +1,287 @@
+use crate::cli::Args;
+use crate::poly::plain::{DensePolynomial, Shrink};
+use crate::sumcheck::interleaved::InterleavedPolys;
+use crate::sumcheck::simd::SIMDPolynomial;
+use crate::sumcheck::{cubic_claim, Backend, CubicSumcheckProof, ProverOptions, RoundStrategy};
+use ark_bn254::Fr;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+pub mod autotune;
+pub mod baseline;
+mod inputs;
+mod report;
+pub mod roofline;
+pub mod sweep;
+
+pub use inputs::Distribution;
+pub use report::{BenchReport, BenchResult, PhaseStats, RoundStats};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("unknown format '{s}' (expected table, json or csv)")),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BenchConfig {
+    pub log_sizes: Vec<usize>,
+    pub backends: Vec<Backend>,
+    pub threads: Vec<usize>,
+    pub reps: usize,
+    pub distributions: Vec<Distribution>,
+    /// How the provers release the upper halves freed by each bind.
+    pub shrinks: Vec<Shrink>,
+    /// Round sizes at which the CPU provers switch between sequential, reduced and full parallelism.
+    pub strategy: RoundStrategy,
+    /// Verify every proof against the claim and require all backends to produce the same proof.
+    pub check_proofs: bool,
+    /// Wrap every run in a tracing-texray span.
+    pub texray: bool,
+    /// Also time the backend's single-polynomial bind on the proof's challenges, the
+    /// `bound_poly_var_top_par` phase of baselines. Off, that phase is left at zero.
+    pub time_bound_poly: bool,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            log_sizes: vec![28],
+            backends: Backend::ALL.to_vec(),
+            threads: vec![rayon::current_num_threads()],
+            reps: 1,
+            distributions: vec![Distribution::Sequential],
+            shrinks: vec![Shrink::Keep],
+            strategy: RoundStrategy::default(),
+            check_proofs: false,
+            texray: false,
+            time_bound_poly: false,
+        }
+    }
+}
+
+impl BenchConfig {
+    pub fn from_args(args: &Args) -> Result<Self, String> {
+        let default = Self::default();
+        Ok(BenchConfig {
+            log_sizes: args
+                .get("log-sizes")
+                .map_or(Ok(default.log_sizes), parse_usize_list)?,
+            backends: args
+                .get("backends")
+                .map_or(Ok(default.backends), parse_list)?,
+            threads: args
+                .get("threads")
+                .map_or(Ok(default.threads), parse_usize_list)?,
+            reps: args.parse_or("reps", default.reps)?.max(1),
+            distributions: args
+                .get("dists")
+                .map_or(Ok(default.distributions), parse_list)?,
+            shrinks: args
+                .get("shrink")
+                .map_or(Ok(default.shrinks), parse_list)?,
+            strategy: RoundStrategy::new(
+                args.parse_or("sequential-below", default.strategy.sequential_below)?,
+                args.parse_or("parallel-from", default.strategy.parallel_from)?,
+            )?,
+            check_proofs: args.has("check"),
+            texray: args.has("texray"),
+            time_bound_poly: args.has("baseline") || args.has("save-baseline"),
+        })
+    }
+}
+
+/// Parses a comma separated list.
+pub fn parse_list<T: std::str::FromStr<Err = String>>(s: &str) -> Result<Vec<T>, String> {
+    s.split(',').map(|item| item.trim().parse()).collect()
+}
+
+/// Parses a comma separated list of integers and inclusive ranges, e.g. "16,20-24".
+pub fn parse_usize_list(s: &str) -> Result<Vec<usize>, String> {
+    let parse = |v: &str| {
+        v.trim()
+            .parse::<usize>()
+            .map_err(|e| format!("invalid integer '{v}': {e}"))
+    };
+    let mut values = Vec::new();
+    for item in s.split(',') {
+        match item.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi) = (parse(lo)?, parse(hi)?);
+                if lo > hi {
+                    return Err(format!("range '{}' is reversed", item.trim()));
+                }
+                values.extend(lo..=hi);
+            }
+            None => values.push(parse(item)?),
+        }
+    }
+    Ok(values)
+}
+
+pub fn run(config: &BenchConfig) -> Result<BenchReport, Box<dyn Error>> {
+    let mut report = BenchReport::default();
+
+    for &log_size in &config.log_sizes {
+        for &dist in &config.distributions {
+            let (eq, a, b) = dist.inputs(log_size);
+            let claim = config.check_proofs.then(|| cubic_claim(&eq, &a, &b));
+
+            for &threads in &config.threads {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()?;
+                let mut reference: Option<(Backend, CubicSumcheckProof)> = None;
+
+                for (&backend, &shrink) in config
+                    .backends
+                    .iter()
+                    .flat_map(|b| config.shrinks.iter().map(move |s| (b, s)))
+                {
+                    let options = ProverOptions {
+                        shrink,
+                        strategy: config.strategy,
+                    };
+                    let mut runs = Vec::with_capacity(config.reps);
+                    let mut bound_poly_runs = Vec::with_capacity(config.reps);
+                    for _ in 0..config.reps {
+                        let inputs = (eq.clone(), a.clone(), b.clone());
+                        let (proof, timings) = pool.install(|| {
+                            let (eq, a, b) = inputs;
+                            if config.texray {
+                                tracing_texray::examine(tracing::info_span!(
+                                    "bench_sumcheck",
+                                    backend = backend.name()
+                                ))
+                                .in_scope(|| backend.prove_timed(eq, a, b, options))
+                            } else {
+                                backend.prove_timed(eq, a, b, options)
+                            }
+                        });
+                        if config.time_bound_poly {
+                            bound_poly_runs.push(
+                                pool.install(|| time_bound_poly(backend, eq.clone(), &proof.rs)),
+                            );
+                        }
+
+                        if let Some(claim) = &claim {
+                            proof.verify(claim)?;
+                            match &reference {
+                                Some((ref_backend, ref_proof)) if *ref_proof != proof => {
+                                    return Err(format!(
+                                        "{backend} proof differs from {ref_backend} proof \
+                                         (log_size {log_size}, {dist}, {threads} threads, \
+                                         shrink {shrink})"
+                                    )
+                                    .into());
+                                }
+                                Some(_) => {}
+                                None => reference = Some((backend, proof)),
+                            }
+                        }
+                        runs.push(timings);
+                    }
+
+                    report.results.push(BenchResult::new(
+                        backend.to_string(),
+                        log_size,
+                        threads,
+                        dist.to_string(),
+                        shrink.to_string(),
+                        &runs,
+                        &bound_poly_runs,
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Times the backend's single-polynomial top binding for every challenge in `rs`. The interleaved
+/// layout has no single-polynomial kernel, so it binds `evals` as all three of its polys.
+fn time_bound_poly(backend: Backend, evals: Vec<Fr>, rs: &[Fr]) -> Vec<Duration> {
+    fn time_rounds(rs: &[Fr], mut bind: impl FnMut(&Fr)) -> Vec<Duration> {
+        rs.iter()
+            .map(|r| {
+                let start = Instant::now();
+                bind(r);
+                start.elapsed()
+            })
+            .collect()
+    }
+
+    match backend {
+        Backend::Plain => {
+            let mut poly = DensePolynomial::new(evals);
+            time_rounds(rs, |r| poly.bound_poly_var_top_par(r))
+        }
+        Backend::Simd => {
+            let mut poly = SIMDPolynomial { Z: evals };
+            time_rounds(rs, |r| poly.bound_poly_var_top_par(r))
+        }
+        Backend::Interleaved => {
+            let mut polys = InterleavedPolys::from_split(&evals, &evals, &evals);
+            time_rounds(rs, |r| polys.bound_poly_var_top_par(r))
+        }
+        #[cfg(feature = "gpu")]
+        Backend::Gpu => {
+            let mut poly = crate::poly::gpu::GPUPoly::new(evals);
+            time_rounds(rs, |r| poly.bound_poly_var_top(r))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usize_lists() {
+        assert_eq!(parse_usize_list("16").unwrap(), vec![16]);
+        assert_eq!(parse_usize_list("4,8-10").unwrap(), vec![4, 8, 9, 10]);
+        assert!(parse_usize_list("4,x").is_err());
+        assert!(parse_usize_list("28-20").is_err());
+    }
+
+    #[test]
+    fn checked_run_across_backends() {
+        let config = BenchConfig {
+            log_sizes: vec![4, 5],
+            threads: vec![1, 2],
+            reps: 3,
+            distributions: vec![Distribution::Random, Distribution::Sparse],
+            shrinks: vec![Shrink::Keep, Shrink::Fit, Shrink::Reallocate],
+            check_proofs: true,
+            ..BenchConfig::default()
+        };
+        let report = run(&config).unwrap();
+        assert_eq!(report.results.len(), 2 * 2 * 2 * 3 * config.backends.len());
+        assert!(report.results.iter().all(|r| r.reps == 3));
+        assert_eq!(report.results[0].rounds.len(), 4);
+        assert!(report.results.iter().all(|r| r.bound_poly.median_ns == 0));
+
+        let config = BenchConfig {
+            log_sizes: vec![4],
+            time_bound_poly: true,
+            ..BenchConfig::default()
+        };
+        let report = run(&config).unwrap();
+        assert!(report.results.iter().all(|r| r.bound_poly.median_ns > 0));
+    }
+}
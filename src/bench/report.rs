@@ -0,0 +1,280 @@
+use crate::sumcheck::RoundTiming;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+}
+
+impl PhaseStats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        // Nearest-rank percentiles.
+        let rank = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        PhaseStats {
+            min_ns: samples[0].as_nanos() as u64,
+            median_ns: rank(50).as_nanos() as u64,
+            p95_ns: rank(95).as_nanos() as u64,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoundStats {
+    pub round: usize,
+    pub eval: PhaseStats,
+    pub bind: PhaseStats,
+    pub transcript: PhaseStats,
+    pub bound_poly: PhaseStats,
+    /// Live heap after the round's bind, maximum over reps (zero without `track-alloc`).
+    #[serde(default)]
+    pub resident_bytes: usize,
+    /// Peak heap during the round, maximum over reps (zero without `track-alloc`).
+    #[serde(default)]
+    pub peak_bytes: usize,
+}
+
+/// Timings for one (backend, log_size, threads, distribution, shrink) configuration across all reps.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub backend: String,
+    pub log_size: usize,
+    pub threads: usize,
+    pub distribution: String,
+    /// Baselines saved before shrink modes existed ran with the default.
+    #[serde(default = "default_shrink")]
+    pub shrink: String,
+    pub reps: usize,
+    pub total: PhaseStats,
+    pub eval: PhaseStats,
+    pub bind: PhaseStats,
+    pub transcript: PhaseStats,
+    /// Binding a single polynomial with the backend's `bound_poly_var_top(_par)`, outside the sumcheck.
+    /// Zero unless `BenchConfig::time_bound_poly` is set.
+    pub bound_poly: PhaseStats,
+    /// Peak heap over the whole sumcheck (zero without `track-alloc`).
+    #[serde(default)]
+    pub peak_bytes: usize,
+    pub rounds: Vec<RoundStats>,
+}
+
+impl BenchResult {
+    /// `runs[rep][round]` holds the timing of a single round of a single rep, `bound_poly` likewise.
+    pub fn new(
+        backend: String,
+        log_size: usize,
+        threads: usize,
+        distribution: String,
+        shrink: String,
+        runs: &[Vec<RoundTiming>],
+        bound_poly: &[Vec<Duration>],
+    ) -> Self {
+        let phase_totals = |phase: fn(&RoundTiming) -> Duration| {
+            PhaseStats::from_samples(runs.iter().map(|run| run.iter().map(phase).sum()).collect())
+        };
+        let round_samples = |round: usize, phase: fn(&RoundTiming) -> Duration| {
+            PhaseStats::from_samples(runs.iter().map(|run| phase(&run[round])).collect())
+        };
+        let round_max = |round: usize, bytes: fn(&RoundTiming) -> usize| {
+            runs.iter().map(|run| bytes(&run[round])).max().unwrap_or(0)
+        };
+
+        let num_rounds = runs.first().map_or(0, Vec::len);
+        let rounds = (0..num_rounds)
+            .map(|round| RoundStats {
+                round,
+                eval: round_samples(round, |t| t.eval),
+                bind: round_samples(round, |t| t.bind),
+                transcript: round_samples(round, |t| t.transcript),
+                bound_poly: PhaseStats::from_samples(
+                    bound_poly.iter().map(|run| run[round]).collect(),
+                ),
+                resident_bytes: round_max(round, |t| t.resident_bytes),
+                peak_bytes: round_max(round, |t| t.peak_bytes),
+            })
+            .collect::<Vec<_>>();
+
+        BenchResult {
+            backend,
+            log_size,
+            threads,
+            distribution,
+            shrink,
+            reps: runs.len(),
+            total: phase_totals(|t| t.eval + t.bind + t.transcript),
+            eval: phase_totals(|t| t.eval),
+            bind: phase_totals(|t| t.bind),
+            transcript: phase_totals(|t| t.transcript),
+            bound_poly: PhaseStats::from_samples(
+                bound_poly.iter().map(|run| run.iter().sum()).collect(),
+            ),
+            peak_bytes: rounds.iter().map(|r| r.peak_bytes).max().unwrap_or(0),
+            rounds,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub results: Vec<BenchResult>,
+}
+
+fn default_shrink() -> String {
+    crate::poly::plain::Shrink::default().to_string()
+}
+
+fn ms(ns: u64) -> f64 {
+    ns as f64 / 1e6
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1 << 20) as f64
+}
+
+impl BenchReport {
+    /// Memory columns are only shown when the tracking allocator is installed.
+    pub fn write_table(&self, out: &mut impl Write, per_round: bool) -> std::io::Result<()> {
+        let tracking = crate::memory::is_tracking();
+        write!(
+            out,
+            "{:<11} {:>4} {:>7} {:<10} {:<7} {:>4} | {:>28} | {:>28} | {:>28} | {:>10} | {:>13}",
+            "backend",
+            "log",
+            "threads",
+            "dist",
+            "shrink",
+            "reps",
+            "total ms (min/med/p95)",
+            "eval ms (min/med/p95)",
+            "bind ms (min/med/p95)",
+            "fs ms (med)",
+            "poly ms (med)"
+        )?;
+        if tracking {
+            write!(out, " | {:>12}", "peak MiB")?;
+        }
+        writeln!(out)?;
+        let fmt = |s: &PhaseStats| {
+            format!(
+                "{:>8.3} {:>9.3} {:>9.3}",
+                ms(s.min_ns),
+                ms(s.median_ns),
+                ms(s.p95_ns)
+            )
+        };
+        for r in &self.results {
+            write!(
+                out,
+                "{:<11} {:>4} {:>7} {:<10} {:<7} {:>4} | {} | {} | {} | {:>10.3} | {:>13.3}",
+                r.backend,
+                r.log_size,
+                r.threads,
+                r.distribution,
+                r.shrink,
+                r.reps,
+                fmt(&r.total),
+                fmt(&r.eval),
+                fmt(&r.bind),
+                ms(r.transcript.median_ns),
+                ms(r.bound_poly.median_ns)
+            )?;
+            if tracking {
+                write!(out, " | {:>12.1}", mib(r.peak_bytes))?;
+            }
+            writeln!(out)?;
+            if per_round {
+                for round in &r.rounds {
+                    write!(
+                        out,
+                        "    round {:>2}: eval {:>10.3} ms  bind {:>10.3} ms  poly {:>10.3} ms",
+                        round.round,
+                        ms(round.eval.median_ns),
+                        ms(round.bind.median_ns),
+                        ms(round.bound_poly.median_ns)
+                    )?;
+                    if tracking {
+                        write!(
+                            out,
+                            "  resident {:>10.1} MiB  peak {:>10.1} MiB",
+                            mib(round.resident_bytes),
+                            mib(round.peak_bytes)
+                        )?;
+                    }
+                    writeln!(out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)
+    }
+
+    /// Long format: one row per (result, round, phase); round is "all" for the per-run totals.
+    /// The byte columns are zero unless built with `track-alloc`.
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "backend,log_size,threads,distribution,shrink,reps,round,phase,min_ns,median_ns,p95_ns,\
+             resident_bytes,peak_bytes"
+        )?;
+        for r in &self.results {
+            let mut row = |round: &str, phase: &str, s: &PhaseStats, resident: usize, peak: usize| {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{round},{phase},{},{},{},{resident},{peak}",
+                    r.backend,
+                    r.log_size,
+                    r.threads,
+                    r.distribution,
+                    r.shrink,
+                    r.reps,
+                    s.min_ns,
+                    s.median_ns,
+                    s.p95_ns
+                )
+            };
+            let final_resident = r.rounds.last().map_or(0, |round| round.resident_bytes);
+            row("all", "total", &r.total, final_resident, r.peak_bytes)?;
+            row("all", "eval", &r.eval, final_resident, r.peak_bytes)?;
+            row("all", "bind", &r.bind, final_resident, r.peak_bytes)?;
+            row("all", "transcript", &r.transcript, final_resident, r.peak_bytes)?;
+            row("all", "bound_poly", &r.bound_poly, final_resident, r.peak_bytes)?;
+            for round in &r.rounds {
+                let index = round.round.to_string();
+                let (resident, peak) = (round.resident_bytes, round.peak_bytes);
+                row(&index, "eval", &round.eval, resident, peak)?;
+                row(&index, "bind", &round.bind, resident, peak)?;
+                row(&index, "transcript", &round.transcript, resident, peak)?;
+                row(&index, "bound_poly", &round.bound_poly, resident, peak)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles() {
+        let samples = (1..=20).map(Duration::from_nanos).collect();
+        let stats = PhaseStats::from_samples(samples);
+        assert_eq!(stats.min_ns, 1);
+        assert_eq!(stats.median_ns, 10);
+        assert_eq!(stats.p95_ns, 19);
+
+        let stats = PhaseStats::from_samples(vec![Duration::from_nanos(7)]);
+        assert_eq!((stats.min_ns, stats.median_ns, stats.p95_ns), (7, 7, 7));
+    }
+}
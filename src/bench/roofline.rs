@@ -0,0 +1,271 @@
+use super::report::BenchReport;
+use super::sweep::round_bytes;
+use ark_bn254::Fr;
+use ark_ff::UniformRand;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::hint::black_box;
+use std::io::Write;
+use std::time::Instant;
+
+/// Field multiplications in one round on 2^(log_size - round) entries, matching the plain prover's
+/// op counts: eval does eq * a * b at four points per pair, bind does one per pair for each of
+/// eq, a and b.
+pub fn round_muls(log_size: usize, round: usize) -> (usize, usize) {
+    let half = 1 << (log_size - round - 1);
+    (8 * half, 3 * half)
+}
+
+/// Host throughput ceilings measured with `threads` threads.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Peaks {
+    pub threads: usize,
+    /// STREAM triad bandwidth, counting one read of each input and one write of the output.
+    pub stream_gbs: f64,
+    /// Independent Montgomery multiplications on cache-resident operands.
+    pub field_mul_gmuls: f64,
+}
+
+impl Peaks {
+    /// Runs both microbenchmarks in the current rayon pool, keeping the best of a few reps.
+    /// `stream_bytes` is the size of each of the three triad arrays and should exceed the last
+    /// level cache.
+    pub fn measure(stream_bytes: usize) -> Self {
+        const REPS: usize = 5;
+        Peaks {
+            threads: rayon::current_num_threads(),
+            stream_gbs: stream_triad_gbs(stream_bytes / 8, REPS),
+            field_mul_gmuls: field_mul_gmuls(REPS),
+        }
+    }
+
+    /// Multiplications per byte at which the two ceilings cross.
+    pub fn ridge(&self) -> f64 {
+        self.field_mul_gmuls / self.stream_gbs
+    }
+}
+
+fn stream_triad_gbs(len: usize, reps: usize) -> f64 {
+    let b = vec![1u64; len];
+    let c = vec![2u64; len];
+    let mut a = vec![0u64; len];
+    let mut best = f64::MAX;
+    for _ in 0..reps {
+        let start = Instant::now();
+        a.par_iter_mut()
+            .zip(b.par_iter())
+            .zip(c.par_iter())
+            .for_each(|((a, b), c)| *a = b.wrapping_add(c.wrapping_mul(3)));
+        best = best.min(start.elapsed().as_secs_f64());
+        black_box(&a);
+    }
+    (3 * len * 8) as f64 / best / 1e9
+}
+
+fn field_mul_gmuls(reps: usize) -> f64 {
+    // 256 independent chains per thread keep the multiplier busy without leaving L1.
+    const LANES: usize = 256;
+    const ITERS: usize = 1 << 12;
+    let threads = rayon::current_num_threads();
+    let mut rng = ark_std::test_rng();
+    let m = Fr::rand(&mut rng);
+    let seed: Vec<Fr> = (0..LANES).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut best = f64::MAX;
+    for _ in 0..reps {
+        let start = Instant::now();
+        (0..threads).into_par_iter().for_each(|_| {
+            let mut lanes = seed.clone();
+            for _ in 0..ITERS {
+                for x in lanes.iter_mut() {
+                    *x *= m;
+                }
+            }
+            black_box(&lanes);
+        });
+        best = best.min(start.elapsed().as_secs_f64());
+    }
+    (threads * LANES * ITERS) as f64 / best / 1e9
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RooflinePoint {
+    pub backend: String,
+    pub distribution: String,
+    pub shrink: String,
+    pub log_size: usize,
+    pub threads: usize,
+    pub round: usize,
+    /// "eval_cubic_top" or "bind_top".
+    pub kernel: &'static str,
+    pub bytes: usize,
+    pub muls: usize,
+    pub median_ns: u64,
+    pub gbs: f64,
+    pub gmuls: f64,
+    /// Achieved bandwidth over the STREAM ceiling.
+    pub bandwidth_fraction: f64,
+    /// Achieved multiplication rate over the field-mul ceiling.
+    pub mul_fraction: f64,
+    /// "memory" or "compute": whichever ceiling the kernel is closer to.
+    pub bound: &'static str,
+}
+
+/// What the bytes and multiplications of every point are counted from.
+pub const MODEL: &str = "plain prover op/byte model (round_bytes, round_muls), for every backend";
+
+/// Per-round throughput of eval and bind against the host ceilings. Every backend is placed
+/// with the plain prover's byte and multiplication counts (`MODEL`), not counts of its own, so
+/// a backend that moves or multiplies more or less than plain shows up as a rate change. GPU
+/// rounds are measured against the same host ceilings, so their fractions only say how they
+/// compare to the CPU.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RooflineReport {
+    pub model: &'static str,
+    pub peaks: Vec<Peaks>,
+    pub points: Vec<RooflinePoint>,
+}
+
+impl RooflineReport {
+    /// `peaks` needs an entry for every thread count in `report`; results without one are skipped.
+    pub fn new(report: &BenchReport, peaks: Vec<Peaks>) -> Self {
+        let mut points = Vec::new();
+        for r in &report.results {
+            let Some(peak) = peaks.iter().find(|p| p.threads == r.threads) else {
+                continue;
+            };
+            for round in &r.rounds {
+                let (eval_bytes, bind_bytes) = round_bytes(r.log_size, round.round);
+                let (eval_muls, bind_muls) = round_muls(r.log_size, round.round);
+                for (kernel, bytes, muls, stats) in [
+                    ("eval_cubic_top", eval_bytes, eval_muls, &round.eval),
+                    ("bind_top", bind_bytes, bind_muls, &round.bind),
+                ] {
+                    let ns = stats.median_ns.max(1) as f64;
+                    let gbs = bytes as f64 / ns;
+                    let gmuls = muls as f64 / ns;
+                    let bandwidth_fraction = gbs / peak.stream_gbs;
+                    let mul_fraction = gmuls / peak.field_mul_gmuls;
+                    points.push(RooflinePoint {
+                        backend: r.backend.clone(),
+                        distribution: r.distribution.clone(),
+                        shrink: r.shrink.clone(),
+                        log_size: r.log_size,
+                        threads: r.threads,
+                        round: round.round,
+                        kernel,
+                        bytes,
+                        muls,
+                        median_ns: stats.median_ns,
+                        gbs,
+                        gmuls,
+                        bandwidth_fraction,
+                        mul_fraction,
+                        bound: if bandwidth_fraction >= mul_fraction {
+                            "memory"
+                        } else {
+                            "compute"
+                        },
+                    });
+                }
+            }
+        }
+        RooflineReport {
+            model: MODEL,
+            peaks,
+            points,
+        }
+    }
+
+    /// The model and ceilings, then one block per configuration with a row per round and kernel.
+    pub fn write_table(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "counts: {}", self.model)?;
+        for p in &self.peaks {
+            writeln!(
+                out,
+                "{:>3} threads: stream {:>7.1} GB/s, field mul {:>6.3} Gmul/s, ridge {:.4} mul/B",
+                p.threads,
+                p.stream_gbs,
+                p.field_mul_gmuls,
+                p.ridge()
+            )?;
+        }
+        writeln!(out)?;
+
+        let mut previous = None;
+        for p in &self.points {
+            let key = (&p.backend, &p.distribution, &p.shrink, p.log_size, p.threads);
+            if previous != Some(key) {
+                previous = Some(key);
+                writeln!(
+                    out,
+                    "{} / {} / shrink {} / log_size {} / {} threads",
+                    p.backend, p.distribution, p.shrink, p.log_size, p.threads
+                )?;
+                writeln!(
+                    out,
+                    "{:>5} {:<14} {:>12} {:>12} {:>10} {:>8} {:>6} {:>8} {:>6}  bound",
+                    "round", "kernel", "MB", "Mmul", "ms", "GB/s", "%bw", "Gmul/s", "%mul"
+                )?;
+            }
+            writeln!(
+                out,
+                "{:>5} {:<14} {:>12.2} {:>12.2} {:>10.3} {:>8.2} {:>5.0}% {:>8.3} {:>5.0}%  {}",
+                p.round,
+                p.kernel,
+                p.bytes as f64 / 1e6,
+                p.muls as f64 / 1e6,
+                p.median_ns as f64 / 1e6,
+                p.gbs,
+                p.bandwidth_fraction * 100.0,
+                p.gmuls,
+                p.mul_fraction * 100.0,
+                p.bound
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{run, BenchConfig};
+
+    #[test]
+    fn roofline_points() {
+        assert_eq!(round_muls(4, 0), (8 * 8, 3 * 8));
+        assert_eq!(round_muls(4, 3), (8, 3));
+
+        let config = BenchConfig {
+            log_sizes: vec![6],
+            threads: vec![2],
+            ..BenchConfig::default()
+        };
+        let report = run(&config).unwrap();
+        let peaks = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap()
+            .install(|| Peaks::measure(1 << 20));
+        assert_eq!(peaks.threads, 2);
+        assert!(peaks.stream_gbs > 0.0 && peaks.field_mul_gmuls > 0.0);
+
+        let roofline = RooflineReport::new(&report, vec![peaks]);
+        assert_eq!(roofline.model, MODEL);
+        let mut table = Vec::new();
+        roofline.write_table(&mut table).unwrap();
+        assert!(String::from_utf8(table).unwrap().starts_with("counts: plain prover"));
+        // eval and bind for each of 6 rounds, per backend.
+        assert_eq!(roofline.points.len(), config.backends.len() * 6 * 2);
+        assert!(roofline
+            .points
+            .iter()
+            .all(|p| p.gbs.is_finite() && p.gmuls.is_finite()));
+    }
+}
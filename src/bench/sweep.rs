@@ -0,0 +1,227 @@
+use super::report::BenchReport;
+use super::BenchConfig;
+use crate::cli::Args;
+use ark_bn254::Fr;
+use serde::Serialize;
+use std::io::Write;
+
+/// Bytes read and written by one round on 2^(log_size - round) entries of each of eq, a and b:
+/// eval reads both halves of all three, bind additionally writes the lower halves.
+pub fn round_bytes(log_size: usize, round: usize) -> (usize, usize) {
+    let len = 1 << (log_size - round);
+    let fr = std::mem::size_of::<Fr>();
+    let eval = 3 * len * fr;
+    let bind = 3 * (len + len / 2) * fr;
+    (eval, bind)
+}
+
+/// Like `BenchConfig::from_args`, but defaults to a power-of-two thread grid up to the core count.
+pub fn sweep_config(args: &Args) -> Result<BenchConfig, String> {
+    let mut config = BenchConfig::from_args(args)?;
+    if !args.has("log-sizes") {
+        config.log_sizes = vec![16, 20, 24];
+    }
+    if !args.has("threads") {
+        let max = std::thread::available_parallelism().map_or(1, |n| n.get());
+        config.threads = (0..)
+            .map(|i| 1 << i)
+            .take_while(|&t| t < max)
+            .chain([max])
+            .collect();
+    }
+    Ok(config)
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SweepPoint {
+    pub backend: String,
+    pub distribution: String,
+    pub log_size: usize,
+    pub threads: usize,
+    /// `None` for the whole sumcheck.
+    pub round: Option<usize>,
+    pub elements: usize,
+    pub median_ns: u64,
+    /// Relative to the smallest thread count in the sweep.
+    pub speedup: f64,
+    /// speedup / (threads / smallest thread count).
+    pub efficiency: f64,
+    pub bandwidth_gbs: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SweepReport {
+    pub points: Vec<SweepPoint>,
+}
+
+impl SweepReport {
+    pub fn new(report: &BenchReport) -> Self {
+        let mut points = Vec::new();
+        for r in &report.results {
+            let Some(base) = report
+                .results
+                .iter()
+                .filter(|b| {
+                    b.backend == r.backend
+                        && b.log_size == r.log_size
+                        && b.distribution == r.distribution
+                        && b.shrink == r.shrink
+                })
+                .min_by_key(|b| b.threads)
+            else {
+                continue;
+            };
+            let thread_ratio = r.threads as f64 / base.threads as f64;
+            let mut point = |round: Option<usize>, elements, bytes: usize, ns: u64, base_ns: u64| {
+                let speedup = base_ns as f64 / ns.max(1) as f64;
+                points.push(SweepPoint {
+                    backend: r.backend.clone(),
+                    distribution: r.distribution.clone(),
+                    log_size: r.log_size,
+                    threads: r.threads,
+                    round,
+                    elements,
+                    median_ns: ns,
+                    speedup,
+                    efficiency: speedup / thread_ratio,
+                    bandwidth_gbs: bytes as f64 / ns.max(1) as f64,
+                });
+            };
+
+            let round_ns = |rounds: &[super::RoundStats], i: usize| {
+                let s = &rounds[i];
+                s.eval.median_ns + s.bind.median_ns + s.transcript.median_ns
+            };
+            let mut total_bytes = 0;
+            for i in 0..r.rounds.len() {
+                let (eval, bind) = round_bytes(r.log_size, i);
+                total_bytes += eval + bind;
+                point(
+                    Some(i),
+                    1 << (r.log_size - i),
+                    eval + bind,
+                    round_ns(&r.rounds, i),
+                    round_ns(&base.rounds, i),
+                );
+            }
+            point(
+                None,
+                1 << r.log_size,
+                total_bytes,
+                r.total.median_ns,
+                base.total.median_ns,
+            );
+        }
+        SweepReport { points }
+    }
+
+    /// One block per (backend, distribution, log_size) with a row per round and a column per thread count.
+    pub fn write_table(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut groups: Vec<(&str, &str, usize)> = Vec::new();
+        for p in &self.points {
+            let key = (p.backend.as_str(), p.distribution.as_str(), p.log_size);
+            if !groups.contains(&key) {
+                groups.push(key);
+            }
+        }
+
+        for (backend, distribution, log_size) in groups {
+            let group: Vec<&SweepPoint> = self
+                .points
+                .iter()
+                .filter(|p| {
+                    p.backend == backend && p.distribution == distribution && p.log_size == log_size
+                })
+                .collect();
+            let mut threads: Vec<usize> = group.iter().map(|p| p.threads).collect();
+            threads.sort_unstable();
+            threads.dedup();
+            let mut rounds: Vec<Option<usize>> = group.iter().map(|p| p.round).collect();
+            rounds.sort_unstable_by_key(|r| r.unwrap_or(usize::MAX));
+            rounds.dedup();
+
+            writeln!(out, "{backend} / {distribution} / log_size {log_size}")?;
+            write!(out, "{:>6} {:>10}", "round", "elements")?;
+            for t in &threads {
+                write!(out, " | {:>32}", format!("{t} threads: ms  speedup eff GB/s"))?;
+            }
+            writeln!(out)?;
+            for round in rounds {
+                let label = round.map_or("total".to_string(), |r| r.to_string());
+                let elements = group.iter().find(|p| p.round == round).unwrap().elements;
+                write!(out, "{label:>6} {elements:>10}")?;
+                for t in &threads {
+                    match group.iter().find(|p| p.round == round && p.threads == *t) {
+                        Some(p) => write!(
+                            out,
+                            " | {:>10.3} {:>7.2}x {:>4.0}% {:>6.1}",
+                            p.median_ns as f64 / 1e6,
+                            p.speedup,
+                            p.efficiency * 100.0,
+                            p.bandwidth_gbs
+                        )?,
+                        None => write!(out, " | {:>32}", "-")?,
+                    }
+                }
+                writeln!(out)?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)
+    }
+
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "backend,distribution,log_size,threads,round,elements,median_ns,speedup,efficiency,bandwidth_gbs"
+        )?;
+        for p in &self.points {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{:.4},{:.4},{:.3}",
+                p.backend,
+                p.distribution,
+                p.log_size,
+                p.threads,
+                p.round.map_or("all".to_string(), |r| r.to_string()),
+                p.elements,
+                p.median_ns,
+                p.speedup,
+                p.efficiency,
+                p.bandwidth_gbs
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::run;
+
+    #[test]
+    fn sweep_points() {
+        assert_eq!(round_bytes(4, 0), (3 * 16 * 32, 3 * 24 * 32));
+        assert_eq!(round_bytes(4, 3), (3 * 2 * 32, 3 * 3 * 32));
+
+        let config = BenchConfig {
+            log_sizes: vec![6],
+            threads: vec![1, 2],
+            ..BenchConfig::default()
+        };
+        let report = run(&config).unwrap();
+        let sweep = SweepReport::new(&report);
+        // 6 rounds + total, for each backend and thread count.
+        assert_eq!(sweep.points.len(), config.backends.len() * 2 * 7);
+        for p in sweep.points.iter().filter(|p| p.threads == 1) {
+            assert_eq!(p.speedup, 1.0);
+            assert_eq!(p.efficiency, 1.0);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+/// `--name value` style flags following a subcommand.
+pub struct Args {
+    flags: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut flags = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument '{arg}'"))?;
+            // Flags without a value (e.g. `--json`) are recorded as "true".
+            let value = match iter.as_slice().first() {
+                Some(next) if !next.starts_with("--") => iter.next().unwrap().clone(),
+                _ => "true".to_string(),
+            };
+            flags.insert(name.to_string(), value);
+        }
+        Ok(Args { flags })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).map(String::as_str)
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+
+    pub fn required(&self, name: &str) -> Result<&str, String> {
+        self.get(name).ok_or_else(|| format!("missing required flag --{name}"))
+    }
+
+    pub fn parse_or<T: FromStr>(&self, name: &str, default: T) -> Result<T, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|e| format!("invalid value '{value}' for --{name}: {e}")),
+            None => Ok(default),
+        }
+    }
+}
+
+pub fn read_file<T: CanonicalDeserialize>(path: impl AsRef<Path>) -> Result<T, Box<dyn Error>> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let value = T::deserialize_compressed(&bytes[..])
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(value)
+}
+
+pub fn write_file<T: CanonicalSerialize>(
+    path: impl AsRef<Path>,
+    value: &T,
+) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref();
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes)?;
+    std::fs::write(path, bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(())
+}
@@ -0,0 +1,492 @@
+//! Binary tower fields in the Fan-Paar construction: T_0 = GF(2) and
+//! T_{k+1} = T_k[X_{k+1}] / (X_{k+1}^2 + X_k X_{k+1} + 1) with X_0 = 1, up to T_7 = GF(2^128).
+//!
+//! An element of T_k has 2^k bits: the low half is its constant term in T_{k-1} and the high half
+//! its X_k coefficient. T_j therefore embeds in T_k by zero extension, and the 2^j-bit chunks of a
+//! T_k element are its coordinates over T_j. Addition is xor; multiplication looks up GF(2^8) log
+//! tables at the bottom and runs Karatsuba on the halves above.
+
+use ark_std::rand::distributions::{Distribution, Standard};
+use ark_std::rand::Rng;
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub trait TowerField:
+    Copy
+    + Debug
+    + Default
+    + Eq
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + AddAssign
+    + Mul<Output = Self>
+    + MulAssign
+    + Sum
+{
+    const LEVEL: usize;
+    const BITS: usize = 1 << Self::LEVEL;
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The element whose bits are the low `BITS` bits of `bits`.
+    fn from_bits(bits: u128) -> Self;
+    fn to_bits(self) -> u128;
+    /// self * X_LEVEL, the generator of this level over the one below.
+    fn mul_by_x(self) -> Self;
+    fn inverse(self) -> Option<Self>;
+
+    fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    fn square(self) -> Self {
+        self * self
+    }
+
+    /// The image of an element of a lower level.
+    fn from_subfield<S: TowerField>(s: S) -> Self {
+        debug_assert!(S::LEVEL <= Self::LEVEL);
+        Self::from_bits(s.to_bits())
+    }
+
+    /// self * s for `s` in a lower level, scaling each coordinate over that level instead of
+    /// running the full multiplication.
+    fn mul_subfield<S: TowerField>(self, s: S) -> Self {
+        debug_assert!(S::LEVEL <= Self::LEVEL);
+        if S::LEVEL == 0 {
+            return if s.is_zero() { Self::ZERO } else { self };
+        }
+        // T_1 and T_2 sit in T_3, whose byte-sized coordinates multiply by table lookup.
+        if S::LEVEL < 3 && Self::LEVEL >= 3 {
+            return self.mul_subfield(B8::from_subfield(s));
+        }
+        let bits = self.to_bits();
+        let mut out = 0;
+        for shift in (0..Self::BITS).step_by(S::BITS) {
+            out |= (S::from_bits(bits >> shift) * s).to_bits() << shift;
+        }
+        Self::from_bits(out)
+    }
+}
+
+/// x * y in T_3 from the definition; only used to build the tables.
+const fn mul_slow(level: u32, x: u8, y: u8) -> u8 {
+    if level == 0 {
+        return x & y;
+    }
+    let half = 1 << (level - 1);
+    let mask = (1u8 << half) - 1;
+    let (x0, x1, y0, y1) = (x & mask, x >> half, y & mask, y >> half);
+    let z0 = mul_slow(level - 1, x0, y0);
+    let z2 = mul_slow(level - 1, x1, y1);
+    let z1 = mul_slow(level - 1, x0 ^ x1, y0 ^ y1) ^ z0 ^ z2;
+    let c1 = z1 ^ mul_slow(level - 1, z2, generator(level - 1));
+    (z0 ^ z2) | (c1 << half)
+}
+
+/// The bits of X_level.
+const fn generator(level: u32) -> u8 {
+    if level == 0 {
+        1
+    } else {
+        1 << (1 << (level - 1))
+    }
+}
+
+const fn pow_slow(x: u8, e: usize) -> u8 {
+    let mut out = 1;
+    let mut i = 0;
+    while i < e {
+        out = mul_slow(3, out, x);
+        i += 1;
+    }
+    out
+}
+
+/// EXP[LOG[x] + LOG[y]] = x * y for nonzero x and y in T_3, with EXP doubled to skip the mod 255.
+const TABLES: ([u8; 510], [u8; 256]) = {
+    // The first element whose order is not a proper divisor of 255 = 3 * 5 * 17.
+    let mut g = 2;
+    while pow_slow(g, 85) == 1 || pow_slow(g, 51) == 1 || pow_slow(g, 15) == 1 {
+        g += 1;
+    }
+
+    let (mut exp, mut log) = ([0u8; 510], [0u8; 256]);
+    let mut x = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x;
+        exp[i + 255] = x;
+        log[x as usize] = i as u8;
+        x = mul_slow(3, x, g);
+        i += 1;
+    }
+    (exp, log)
+};
+
+static EXP: [u8; 510] = TABLES.0;
+static LOG: [u8; 256] = TABLES.1;
+
+#[inline(always)]
+fn mul8(x: u8, y: u8) -> u8 {
+    if x == 0 || y == 0 {
+        0
+    } else {
+        EXP[LOG[x as usize] as usize + LOG[y as usize] as usize]
+    }
+}
+
+/// x^-1 for nonzero x in T_3.
+#[inline(always)]
+fn inverse8(x: u8) -> u8 {
+    EXP[255 - LOG[x as usize] as usize]
+}
+
+macro_rules! tower_field {
+    ($(#[$doc:meta])* $name:ident, $repr:ty, $level:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name($repr);
+
+        impl $name {
+            const MASK: $repr = <$repr>::MAX >> (<$repr>::BITS - (1 << $level));
+
+            /// The element with the low bits of `bits`.
+            pub const fn new(bits: $repr) -> Self {
+                $name(bits & Self::MASK)
+            }
+
+            pub const fn bits(self) -> $repr {
+                self.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            // Addition in characteristic 2 is xor, and subtraction is the same.
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            #[inline(always)]
+            fn add(self, other: Self) -> Self {
+                $name(self.0 ^ other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            #[allow(clippy::suspicious_op_assign_impl)]
+            #[inline(always)]
+            fn add_assign(&mut self, other: Self) {
+                self.0 ^= other.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            #[inline(always)]
+            fn sub(self, other: Self) -> Self {
+                self + other
+            }
+        }
+
+        impl SubAssign for $name {
+            #[allow(clippy::suspicious_op_assign_impl)]
+            #[inline(always)]
+            fn sub_assign(&mut self, other: Self) {
+                *self += other;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                self
+            }
+        }
+
+        impl MulAssign for $name {
+            #[inline(always)]
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{:#x}", self.0)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold($name(0), |acc, x| acc + x)
+            }
+        }
+
+        impl Distribution<$name> for Standard {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $name {
+                $name::new(rng.gen())
+            }
+        }
+    };
+}
+
+/// Levels 0 to 3, multiplied through the T_3 tables.
+macro_rules! table_tower_field {
+    ($(#[$doc:meta])* $name:ident, $level:expr) => {
+        tower_field!($(#[$doc])* $name, u8, $level);
+
+        impl Mul for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, other: Self) -> Self {
+                $name(mul8(self.0, other.0))
+            }
+        }
+
+        impl TowerField for $name {
+            const LEVEL: usize = $level;
+            const ZERO: Self = $name(0);
+            const ONE: Self = $name(1);
+
+            #[inline(always)]
+            fn from_bits(bits: u128) -> Self {
+                $name::new(bits as u8)
+            }
+
+            #[inline(always)]
+            fn to_bits(self) -> u128 {
+                self.0 as u128
+            }
+
+            #[inline(always)]
+            fn mul_by_x(self) -> Self {
+                $name(mul8(self.0, generator($level)))
+            }
+
+            fn inverse(self) -> Option<Self> {
+                (self.0 != 0).then(|| $name(inverse8(self.0)))
+            }
+        }
+    };
+}
+
+/// Levels 4 to 7, as pairs of elements of the level below.
+macro_rules! karatsuba_tower_field {
+    ($(#[$doc:meta])* $name:ident, $repr:ty, $level:expr, $half:ident, $half_repr:ty) => {
+        tower_field!($(#[$doc])* $name, $repr, $level);
+
+        impl $name {
+            const HALF_BITS: u32 = 1 << ($level - 1);
+
+            #[inline(always)]
+            fn halves(self) -> ($half, $half) {
+                (
+                    $half(self.0 as $half_repr),
+                    $half((self.0 >> Self::HALF_BITS) as $half_repr),
+                )
+            }
+
+            #[inline(always)]
+            fn from_halves(low: $half, high: $half) -> Self {
+                $name(low.0 as $repr | (high.0 as $repr) << Self::HALF_BITS)
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, other: Self) -> Self {
+                let ((a0, a1), (b0, b1)) = (self.halves(), other.halves());
+                let (z0, z2) = (a0 * b0, a1 * b1);
+                let z1 = (a0 + a1) * (b0 + b1) + z0 + z2;
+                // a1 b1 X^2 = a1 b1 (X_{k-1} X + 1).
+                Self::from_halves(z0 + z2, z1 + z2.mul_by_x())
+            }
+        }
+
+        impl TowerField for $name {
+            const LEVEL: usize = $level;
+            const ZERO: Self = $name(0);
+            const ONE: Self = $name(1);
+
+            #[inline(always)]
+            fn from_bits(bits: u128) -> Self {
+                $name(bits as $repr)
+            }
+
+            #[inline(always)]
+            fn to_bits(self) -> u128 {
+                self.0 as u128
+            }
+
+            #[inline(always)]
+            fn mul_by_x(self) -> Self {
+                let (x0, x1) = self.halves();
+                Self::from_halves(x1, x0 + x1.mul_by_x())
+            }
+
+            fn inverse(self) -> Option<Self> {
+                // (a0 + a1 X)(a0 + a1 X_{k-1} + a1 X) = a0^2 + a0 a1 X_{k-1} + a1^2, one level down.
+                let (a0, a1) = self.halves();
+                let norm = a0.square() + (a0 * a1).mul_by_x() + a1.square();
+                let inv = norm.inverse()?;
+                Some(Self::from_halves((a0 + a1.mul_by_x()) * inv, a1 * inv))
+            }
+        }
+    };
+}
+
+table_tower_field!(
+    /// GF(2).
+    B1,
+    0
+);
+table_tower_field!(
+    /// GF(4); X_1 is a primitive cube root of unity.
+    B2,
+    1
+);
+table_tower_field!(B4, 2);
+table_tower_field!(B8, 3);
+karatsuba_tower_field!(B16, u16, 4, B8, u8);
+karatsuba_tower_field!(B32, u32, 5, B16, u16);
+karatsuba_tower_field!(B64, u64, 6, B32, u32);
+karatsuba_tower_field!(B128, u128, 7, B64, u64);
+
+/// X_1, the element 2 of T_1: with 0, 1 and X_1 + 1 the four points of GF(4).
+pub const OMEGA: B2 = B2(2);
+
+/// Elements of a tower level packed into 128-bit words, 128 / T::BITS to a word; a vector of
+/// bits takes 1/128 of the space it would as `B128`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packed<T: TowerField> {
+    words: Vec<u128>,
+    len: usize,
+    _level: PhantomData<T>,
+}
+
+impl<T: TowerField> Packed<T> {
+    const PER_WORD: usize = 128 >> T::LEVEL;
+
+    pub fn from_slice(values: &[T]) -> Self {
+        let mut words = vec![0u128; values.len().div_ceil(Self::PER_WORD)];
+        for (i, v) in values.iter().enumerate() {
+            words[i / Self::PER_WORD] |= v.to_bits() << (i % Self::PER_WORD * T::BITS);
+        }
+        Packed {
+            words,
+            len: values.len(),
+            _level: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> T {
+        debug_assert!(i < self.len);
+        T::from_bits(self.words[i / Self::PER_WORD] >> (i % Self::PER_WORD * T::BITS))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        (0..self.len).map(|i| self.get(i)).collect()
+    }
+
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.len() * std::mem::size_of::<u128>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_std::test_rng;
+    use ark_std::UniformRand;
+
+    /// Ring axioms on random elements, inverses, and x^(2^BITS) = x, which only holds for every x
+    /// in a field of exactly 2^BITS elements.
+    fn check_field<T: TowerField>()
+    where
+        Standard: Distribution<T>,
+    {
+        let mut rng = test_rng();
+        for _ in 0..16 {
+            let [x, y, z] = [0; 3].map(|_| T::rand(&mut rng));
+            assert_eq!(x * y, y * x);
+            assert_eq!((x * y) * z, x * (y * z));
+            assert_eq!(x * (y + z), x * y + x * z);
+            assert_eq!(x * T::ONE, x);
+            if !x.is_zero() {
+                assert_eq!(x * x.inverse().unwrap(), T::ONE);
+            }
+            let mut frobenius = x;
+            for _ in 0..T::BITS {
+                frobenius = frobenius.square();
+            }
+            assert_eq!(frobenius, x);
+
+            let s = B8::rand(&mut rng);
+            if T::LEVEL >= 3 {
+                assert_eq!(x.mul_subfield(s), x * T::from_subfield(s));
+                assert_eq!(x.mul_subfield(OMEGA), x * T::from_subfield(OMEGA));
+                assert_eq!(x.mul_subfield(B1::ONE), x);
+                assert_eq!(x.mul_subfield(B1::ZERO), T::ZERO);
+            }
+        }
+        assert_eq!(T::ZERO.inverse(), None);
+    }
+
+    #[test]
+    fn tower_fields() {
+        for x in 0..=255 {
+            for y in 0..=255 {
+                assert_eq!(mul8(x, y), mul_slow(3, x, y));
+            }
+        }
+        assert_eq!(OMEGA * OMEGA, OMEGA + B2::ONE);
+        check_field::<B1>();
+        check_field::<B2>();
+        check_field::<B4>();
+        check_field::<B8>();
+        check_field::<B16>();
+        check_field::<B32>();
+        check_field::<B64>();
+        check_field::<B128>();
+
+        // Subfields are closed under the larger multiplication.
+        let mut rng = test_rng();
+        let (x, y) = (B16::rand(&mut rng), B16::rand(&mut rng));
+        assert_eq!(
+            B128::from_subfield(x) * B128::from_subfield(y),
+            B128::from_subfield(x * y)
+        );
+    }
+
+    #[test]
+    fn packed_roundtrip() {
+        let mut rng = test_rng();
+        let bits: Vec<B1> = (0..300).map(|_| B1::rand(&mut rng)).collect();
+        let packed = Packed::from_slice(&bits);
+        assert_eq!(packed.to_vec(), bits);
+        assert_eq!(packed.size_in_bytes(), 3 * 16);
+
+        let nibbles: Vec<B4> = (0..33).map(|_| B4::rand(&mut rng)).collect();
+        assert_eq!(Packed::from_slice(&nibbles).to_vec(), nibbles);
+    }
+}
@@ -0,0 +1,545 @@
+//! A field wrapper that counts the arithmetic performed on it, for comparing the work done by
+//! sumcheck algorithms independently of how fast a particular backend executes it.
+
+use super::lazy::LazySum;
+use ark_ff::{Field, LegendreSymbol, One, SqrtPrecomputation, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalDeserializeWithFlags, CanonicalSerialize,
+    CanonicalSerializeWithFlags, Compress, Flags, SerializationError, Valid, Validate,
+};
+use ark_std::io::{Read, Write};
+use ark_std::rand::distributions::{Distribution, Standard};
+use ark_std::rand::Rng;
+use serde::Serialize;
+use std::cell::OnceCell;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroize;
+
+const MUL: usize = 0;
+const SQUARE: usize = 1;
+const ADD: usize = 2;
+const SUB: usize = 3;
+const INVERSE: usize = 4;
+const REDUCTION: usize = 5;
+
+type Counters = [AtomicU64; 6];
+
+thread_local! {
+    /// The counters of the `count_ops` scope whose pool this thread belongs to. Operations on any
+    /// other thread are not counted.
+    static SCOPE: OnceCell<Arc<Counters>> = const { OnceCell::new() };
+}
+
+#[inline(always)]
+fn bump(counter: usize) {
+    SCOPE.with(|scope| {
+        if let Some(counters) = scope.get() {
+            counters[counter].fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
+
+/// Every multiplication and squaring is followed by one Montgomery reduction, and converting an
+/// integer into Montgomery form costs one more.
+#[inline(always)]
+fn bump_reducing(counter: usize) {
+    bump(counter);
+    bump(REDUCTION);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct OpCounts {
+    pub mul: u64,
+    pub square: u64,
+    pub add: u64,
+    pub sub: u64,
+    pub inverse: u64,
+    pub reduction: u64,
+}
+
+impl OpCounts {
+    fn load(counters: &Counters) -> Self {
+        let load = |counter: usize| counters[counter].load(Ordering::Relaxed);
+        OpCounts {
+            mul: load(MUL),
+            square: load(SQUARE),
+            add: load(ADD),
+            sub: load(SUB),
+            inverse: load(INVERSE),
+            reduction: load(REDUCTION),
+        }
+    }
+}
+
+impl Add for OpCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        OpCounts {
+            mul: self.mul + other.mul,
+            square: self.square + other.square,
+            add: self.add + other.add,
+            sub: self.sub + other.sub,
+            inverse: self.inverse + other.inverse,
+            reduction: self.reduction + other.reduction,
+        }
+    }
+}
+
+impl Sub for OpCounts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        OpCounts {
+            mul: self.mul - other.mul,
+            square: self.square - other.square,
+            add: self.add - other.add,
+            sub: self.sub - other.sub,
+            inverse: self.inverse - other.inverse,
+            reduction: self.reduction - other.reduction,
+        }
+    }
+}
+
+impl Sum for OpCounts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(OpCounts::default(), Add::add)
+    }
+}
+
+/// A thread pool whose threads count into `counters`.
+struct CountingPool {
+    pool: rayon::ThreadPool,
+    counters: Arc<Counters>,
+}
+
+impl CountingPool {
+    fn new(threads: usize) -> Self {
+        let counters: Arc<Counters> = Arc::default();
+        let scope = counters.clone();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .start_handler(move |_| SCOPE.with(|s| s.set(scope.clone()).unwrap()))
+            .build()
+            .expect("failed to build the op-counting thread pool");
+        CountingPool { pool, counters }
+    }
+}
+
+/// Pools not in use by a scope, kept so that each scope does not start threads of its own.
+static IDLE_POOLS: Mutex<Vec<CountingPool>> = Mutex::new(Vec::new());
+
+/// Runs `f` and returns the operations it performed on `Counted` values. `f` runs on a counting
+/// pool with as many threads as the current one, and only that pool's threads count, so
+/// concurrent work elsewhere is left out. Pools are reused across scopes; a nested or concurrent
+/// scope gets one of its own, so its operations count only in that scope.
+pub fn count_ops<R: Send>(f: impl FnOnce() -> R + Send) -> (R, OpCounts) {
+    let threads = rayon::current_num_threads();
+    let idle = {
+        let mut pools = IDLE_POOLS.lock().unwrap();
+        let i = pools
+            .iter()
+            .position(|p| p.pool.current_num_threads() == threads);
+        i.map(|i| pools.swap_remove(i))
+    };
+    let scope = idle.unwrap_or_else(|| CountingPool::new(threads));
+    for counter in scope.counters.iter() {
+        counter.store(0, Ordering::Relaxed);
+    }
+    let result = scope.pool.install(f);
+    let counts = OpCounts::load(&scope.counters);
+    IDLE_POOLS.lock().unwrap().push(scope);
+    (result, counts)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Counted<F: Field>(pub F);
+
+impl<F: Field> std::fmt::Display for Counted<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<F: Field> Zero for Counted<F> {
+    fn zero() -> Self {
+        Counted(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<F: Field> One for Counted<F> {
+    fn one() -> Self {
+        Counted(F::one())
+    }
+}
+
+impl<F: Field> Neg for Counted<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        bump(SUB);
+        Counted(-self.0)
+    }
+}
+
+impl<F: Field> Distribution<Counted<F>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Counted<F> {
+        Counted(F::rand(rng))
+    }
+}
+
+impl<F: Field> Zeroize for Counted<F> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<F: Field> Valid for Counted<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.0.check()
+    }
+}
+
+impl<F: Field> CanonicalSerialize for Counted<F> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_mode(writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.0.serialized_size(compress)
+    }
+}
+
+impl<F: Field> CanonicalSerializeWithFlags for Counted<F> {
+    fn serialize_with_flags<W: Write, Fl: Flags>(
+        &self,
+        writer: W,
+        flags: Fl,
+    ) -> Result<(), SerializationError> {
+        self.0.serialize_with_flags(writer, flags)
+    }
+
+    fn serialized_size_with_flags<Fl: Flags>(&self) -> usize {
+        self.0.serialized_size_with_flags::<Fl>()
+    }
+}
+
+impl<F: Field> CanonicalDeserialize for Counted<F> {
+    fn deserialize_with_mode<R: Read>(
+        reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        F::deserialize_with_mode(reader, compress, validate).map(Counted)
+    }
+}
+
+impl<F: Field> CanonicalDeserializeWithFlags for Counted<F> {
+    fn deserialize_with_flags<R: Read, Fl: Flags>(
+        reader: R,
+    ) -> Result<(Self, Fl), SerializationError> {
+        F::deserialize_with_flags(reader).map(|(f, flags)| (Counted(f), flags))
+    }
+}
+
+macro_rules! impl_counted_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $counter:expr, $bump:ident) => {
+        impl<F: Field> $op for Counted<F> {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                $bump($counter);
+                Counted($op::$method(self.0, other.0))
+            }
+        }
+
+        impl<'a, F: Field> $op<&'a Self> for Counted<F> {
+            type Output = Self;
+
+            fn $method(self, other: &'a Self) -> Self {
+                $op::$method(self, *other)
+            }
+        }
+
+        impl<'a, F: Field> $op<&'a mut Self> for Counted<F> {
+            type Output = Self;
+
+            fn $method(self, other: &'a mut Self) -> Self {
+                $op::$method(self, *other)
+            }
+        }
+
+        impl<F: Field> $op_assign for Counted<F> {
+            fn $method_assign(&mut self, other: Self) {
+                *self = $op::$method(*self, other);
+            }
+        }
+
+        impl<'a, F: Field> $op_assign<&'a Self> for Counted<F> {
+            fn $method_assign(&mut self, other: &'a Self) {
+                *self = $op::$method(*self, *other);
+            }
+        }
+
+        impl<'a, F: Field> $op_assign<&'a mut Self> for Counted<F> {
+            fn $method_assign(&mut self, other: &'a mut Self) {
+                *self = $op::$method(*self, *other);
+            }
+        }
+    };
+}
+
+impl_counted_op!(Add, add, AddAssign, add_assign, ADD, bump);
+impl_counted_op!(Sub, sub, SubAssign, sub_assign, SUB, bump);
+impl_counted_op!(Mul, mul, MulAssign, mul_assign, MUL, bump_reducing);
+
+/// Division is an inversion followed by a multiplication.
+fn counted_div<F: Field>(a: Counted<F>, b: Counted<F>) -> Counted<F> {
+    a * b.inverse().expect("division by zero")
+}
+
+impl<F: Field> Div for Counted<F> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        counted_div(self, other)
+    }
+}
+
+impl<'a, F: Field> Div<&'a Self> for Counted<F> {
+    type Output = Self;
+
+    fn div(self, other: &'a Self) -> Self {
+        counted_div(self, *other)
+    }
+}
+
+impl<'a, F: Field> Div<&'a mut Self> for Counted<F> {
+    type Output = Self;
+
+    fn div(self, other: &'a mut Self) -> Self {
+        counted_div(self, *other)
+    }
+}
+
+impl<F: Field> DivAssign for Counted<F> {
+    fn div_assign(&mut self, other: Self) {
+        *self = counted_div(*self, other);
+    }
+}
+
+impl<'a, F: Field> DivAssign<&'a Self> for Counted<F> {
+    fn div_assign(&mut self, other: &'a Self) {
+        *self = counted_div(*self, *other);
+    }
+}
+
+impl<'a, F: Field> DivAssign<&'a mut Self> for Counted<F> {
+    fn div_assign(&mut self, other: &'a mut Self) {
+        *self = counted_div(*self, *other);
+    }
+}
+
+impl<F: Field> Sum for Counted<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<'a, F: Field> Sum<&'a Self> for Counted<F> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl<F: Field> Product for Counted<F> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+impl<'a, F: Field> Product<&'a Self> for Counted<F> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), Mul::mul)
+    }
+}
+
+macro_rules! impl_counted_from {
+    ($($int:ty),*) => {
+        $(
+            impl<F: Field> From<$int> for Counted<F> {
+                fn from(value: $int) -> Self {
+                    bump(REDUCTION);
+                    Counted(F::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_counted_from!(u128, u64, u32, u16, u8, bool);
+
+impl<F: Field> Field for Counted<F> {
+    type BasePrimeField = F::BasePrimeField;
+    type BasePrimeFieldIter = F::BasePrimeFieldIter;
+
+    // `sqrt` is delegated to `F` below instead.
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Self>> = None;
+    const ZERO: Self = Counted(F::ZERO);
+    const ONE: Self = Counted(F::ONE);
+
+    fn extension_degree() -> u64 {
+        F::extension_degree()
+    }
+
+    fn to_base_prime_field_elements(&self) -> Self::BasePrimeFieldIter {
+        self.0.to_base_prime_field_elements()
+    }
+
+    fn from_base_prime_field_elems(elems: &[Self::BasePrimeField]) -> Option<Self> {
+        F::from_base_prime_field_elems(elems).map(Counted)
+    }
+
+    fn from_base_prime_field(elem: Self::BasePrimeField) -> Self {
+        Counted(F::from_base_prime_field(elem))
+    }
+
+    fn double(&self) -> Self {
+        bump(ADD);
+        Counted(self.0.double())
+    }
+
+    fn double_in_place(&mut self) -> &mut Self {
+        *self = self.double();
+        self
+    }
+
+    fn neg_in_place(&mut self) -> &mut Self {
+        *self = -*self;
+        self
+    }
+
+    fn from_random_bytes_with_flags<Fl: Flags>(bytes: &[u8]) -> Option<(Self, Fl)> {
+        F::from_random_bytes_with_flags(bytes).map(|(f, flags)| (Counted(f), flags))
+    }
+
+    fn legendre(&self) -> LegendreSymbol {
+        self.0.legendre()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        self.0.sqrt().map(Counted)
+    }
+
+    fn square(&self) -> Self {
+        bump_reducing(SQUARE);
+        Counted(self.0.square())
+    }
+
+    fn square_in_place(&mut self) -> &mut Self {
+        *self = self.square();
+        self
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        bump(INVERSE);
+        self.0.inverse().map(Counted)
+    }
+
+    fn inverse_in_place(&mut self) -> Option<&mut Self> {
+        let inverse = self.inverse()?;
+        *self = inverse;
+        Some(self)
+    }
+
+    fn frobenius_map_in_place(&mut self, power: usize) {
+        self.0.frobenius_map_in_place(power);
+    }
+}
+
+/// Wraps every element of `evals` for counting.
+pub fn counted_vec<F: Field>(evals: &[F]) -> Vec<Counted<F>> {
+    evals.iter().copied().map(Counted).collect()
+}
+
+/// Eager: every product is reduced and added, so the counts match an unaccumulated sum.
+impl<F: Field> LazySum for Counted<F> {
+    type Acc = Self;
+
+    fn acc_zero() -> Self {
+        Self::zero()
+    }
+
+    fn mul_add(acc: &mut Self, a: &Self, b: &Self) {
+        *acc += *a * b;
+    }
+
+    fn merge(a: Self, b: Self) -> Self {
+        a + b
+    }
+
+    fn reduce(acc: Self) -> Self {
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn counts_and_values_match_inner_field() {
+        let (a, b) = (Fr::from(7u64), Fr::from(11u64));
+        let (result, counts) = count_ops(|| {
+            let (x, y) = (Counted(a), Counted(b));
+            (x * y + x - y).square() / y
+        });
+
+        assert_eq!(result.0, (a * b + a - b).square() / b);
+        assert_eq!(
+            counts,
+            OpCounts {
+                mul: 2,
+                square: 1,
+                add: 1,
+                sub: 1,
+                inverse: 1,
+                reduction: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn scopes_ignore_other_threads() {
+        let stop = std::sync::atomic::AtomicBool::new(false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut x = Counted(Fr::from(3u64));
+                while !stop.load(Ordering::Relaxed) {
+                    x = x * x + x;
+                }
+            });
+            for _ in 0..20 {
+                let ((), counts) = count_ops(|| {
+                    let x = Counted(Fr::from(5u64));
+                    std::hint::black_box(x * x);
+                });
+                assert_eq!(counts.mul, 1);
+                assert_eq!(counts.add, 0);
+            }
+            stop.store(true, Ordering::Relaxed);
+        });
+    }
+}
@@ -0,0 +1,90 @@
+//! The Goldilocks field p = 2^64 - 2^32 + 1 and its degree-2 and degree-4 extensions u^2 = 7 and
+//! v^4 = 7.
+
+use super::small::{small_fp, SmallFp, SmallPrime};
+use ark_ff::{Fp2, Fp2Config, Fp4, Fp4Config, SqrtPrecomputation};
+
+pub struct GoldilocksPrime;
+
+/// 2^32 - 1 = 2^64 mod p.
+const EPSILON: u64 = (1 << 32) - 1;
+
+impl SmallPrime for GoldilocksPrime {
+    const P: u64 = 0xffff_ffff_0000_0001;
+    const GENERATOR: u64 = 7;
+    const TWO_ADICITY: u32 = 32;
+    const TWO_ADIC_ROOT_OF_UNITY: u64 = 1753635133440165772;
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<Goldilocks>> =
+        Some(SqrtPrecomputation::TonelliShanks {
+            two_adicity: 32,
+            quadratic_nonresidue_to_trace: small_fp(1753635133440165772),
+            trace_of_modulus_minus_one_div_two: &[(1 << 31) - 1],
+        });
+
+    /// Folds the high half with 2^64 = 2^32 - 1 and 2^96 = -1.
+    #[inline(always)]
+    fn reduce(x: u128) -> u64 {
+        let (low, high) = (x as u64, (x >> 64) as u64);
+        let (high_high, high_low) = (high >> 32, high & EPSILON);
+        let (mut t, borrow) = low.overflowing_sub(high_high);
+        if borrow {
+            // t wrapped by 2^64 = EPSILON, and is at least 2^64 - 2^32 + 1.
+            t -= EPSILON;
+        }
+        let (t, carry) = t.overflowing_add(high_low * EPSILON);
+        let t = t.wrapping_add(EPSILON * carry as u64);
+        if t >= Self::P {
+            t - Self::P
+        } else {
+            t
+        }
+    }
+}
+
+pub type Goldilocks = SmallFp<GoldilocksPrime>;
+
+pub struct GoldilocksExt2Config;
+
+impl Fp2Config for GoldilocksExt2Config {
+    type Fp = Goldilocks;
+
+    const NONRESIDUE: Goldilocks = small_fp(7);
+
+    /// 7^((p^i - 1) / 2)
+    const FROBENIUS_COEFF_FP2_C1: &'static [Goldilocks] =
+        &[small_fp(1), small_fp(0xffff_ffff_0000_0000)];
+}
+
+pub type GoldilocksExt2 = Fp2<GoldilocksExt2Config>;
+
+pub struct GoldilocksExt4Config;
+
+impl Fp4Config for GoldilocksExt4Config {
+    type Fp2Config = GoldilocksExt2Config;
+
+    /// v^2 = u, so v^4 = 7.
+    const NONRESIDUE: GoldilocksExt2 = GoldilocksExt2::new(small_fp(0), small_fp(1));
+
+    /// 7^((p^i - 1) / 4)
+    const FROBENIUS_COEFF_FP4_C1: &'static [Goldilocks] = &[
+        small_fp(1),
+        small_fp(1 << 48),
+        small_fp(0xffff_ffff_0000_0000),
+        small_fp(0xfffe_ffff_0000_0001),
+    ];
+}
+
+pub type GoldilocksExt4 = Fp4<GoldilocksExt4Config>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::small::tests::{check_extension, check_prime_field};
+
+    #[test]
+    fn goldilocks_arithmetic() {
+        check_prime_field::<GoldilocksPrime>();
+        check_extension::<GoldilocksExt2>();
+        check_extension::<GoldilocksExt4>();
+    }
+}
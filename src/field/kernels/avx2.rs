@@ -0,0 +1,117 @@
+//! Four elements per 256-bit vector, on the shared radix-2^32 arithmetic.
+
+use super::radix32::{self, Lanes, Limbs};
+use super::Op;
+use crate::field::lazy::WideAcc;
+use ark_bn254::Fr;
+use std::arch::x86_64::*;
+
+impl Lanes for __m256i {
+    const LANES: usize = 4;
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn splat(x: u64) -> Self {
+        _mm256_set1_epi64x(x as i64)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add(self, other: Self) -> Self {
+        _mm256_add_epi64(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sub(self, other: Self) -> Self {
+        _mm256_sub_epi64(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mul(self, other: Self) -> Self {
+        _mm256_mul_epu32(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn and(self, other: Self) -> Self {
+        _mm256_and_si256(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn shr<const N: i32>(self) -> Self {
+        _mm256_srli_epi64::<N>(self)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn select(mask: Self, a: Self, b: Self) -> Self {
+        _mm256_blendv_epi8(b, a, mask)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn load(src: *const Fr) -> Limbs<Self> {
+        let src = src as *const __m256i;
+        let rows = [
+            _mm256_loadu_si256(src),
+            _mm256_loadu_si256(src.add(1)),
+            _mm256_loadu_si256(src.add(2)),
+            _mm256_loadu_si256(src.add(3)),
+        ];
+        let mask = _mm256_set1_epi64x(u32::MAX as i64);
+        let mut limbs = [mask; 8];
+        for (k, column) in transpose(rows).into_iter().enumerate() {
+            limbs[2 * k] = _mm256_and_si256(column, mask);
+            limbs[2 * k + 1] = _mm256_srli_epi64::<32>(column);
+        }
+        limbs
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store(limbs: Limbs<Self>, dst: *mut Fr) {
+        let mut columns = [limbs[0]; 4];
+        for (k, column) in columns.iter_mut().enumerate() {
+            *column = _mm256_or_si256(limbs[2 * k], _mm256_slli_epi64::<32>(limbs[2 * k + 1]));
+        }
+        let dst = dst as *mut __m256i;
+        for (e, row) in transpose(columns).into_iter().enumerate() {
+            _mm256_storeu_si256(dst.add(e), row);
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn store_lanes(self, out: &mut [u64]) {
+        _mm256_storeu_si256(out[..4].as_mut_ptr() as *mut __m256i, self);
+    }
+}
+
+/// Rows of four 64-bit limbs to columns, and back.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn transpose([r0, r1, r2, r3]: [__m256i; 4]) -> [__m256i; 4] {
+    let t0 = _mm256_unpacklo_epi64(r0, r1);
+    let t1 = _mm256_unpackhi_epi64(r0, r1);
+    let t2 = _mm256_unpacklo_epi64(r2, r3);
+    let t3 = _mm256_unpackhi_epi64(r2, r3);
+    [
+        _mm256_permute2x128_si256::<0x20>(t0, t2),
+        _mm256_permute2x128_si256::<0x20>(t1, t3),
+        _mm256_permute2x128_si256::<0x31>(t0, t2),
+        _mm256_permute2x128_si256::<0x31>(t1, t3),
+    ]
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn elementwise(op: Op, a: *const Fr, b: *const Fr, out: *mut Fr, len: usize) {
+    radix32::elementwise::<__m256i>(op, a, b, out, len)
+}
+
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn inner_product(a: &[Fr], b: &[Fr]) -> WideAcc {
+    radix32::inner_product::<__m256i>(a, b)
+}
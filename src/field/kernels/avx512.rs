@@ -0,0 +1,317 @@
+//! Eight elements per 512-bit vector in five 52-bit limbs, multiplied with the IFMA
+//! `vpmadd52{lo,hi}uq` instructions. 2^256 is not a power of 2^52, so the Montgomery reduction
+//! takes four 52-bit steps and a final 48-bit one.
+
+use super::{scalar_tail, Op, P, P_INV};
+use crate::field::lazy::WideAcc;
+use ark_bn254::Fr;
+use std::arch::x86_64::*;
+
+const LANES: usize = 8;
+const LIMBS: usize = 5;
+const MASK: u64 = (1 << 52) - 1;
+
+/// Inner product columns gain at most 10 * 2^52 per vector, so carrying them every 64 vectors
+/// keeps them below 2^63. The top column is flushed to the wide accumulator far less often.
+const CARRY_EVERY: usize = 64;
+const FLUSH_EVERY: usize = 1 << 16;
+
+type Limbs = [__m512i; LIMBS];
+
+const P52: [u64; LIMBS] = [
+    P[0] & MASK,
+    ((P[0] >> 52) | (P[1] << 12)) & MASK,
+    ((P[1] >> 40) | (P[2] << 24)) & MASK,
+    ((P[2] >> 28) | (P[3] << 36)) & MASK,
+    P[3] >> 16,
+];
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn splat(x: u64) -> __m512i {
+    _mm512_set1_epi64(x as i64)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn modulus() -> Limbs {
+    [
+        splat(P52[0]),
+        splat(P52[1]),
+        splat(P52[2]),
+        splat(P52[3]),
+        splat(P52[4]),
+    ]
+}
+
+/// The limbs of the eight elements at `src`: four 64-bit limb columns, split at 52 bits.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn load(src: *const Fr) -> Limbs {
+    let src = src as *const __m512i;
+    let rows = [
+        _mm512_loadu_si512(src),
+        _mm512_loadu_si512(src.add(1)),
+        _mm512_loadu_si512(src.add(2)),
+        _mm512_loadu_si512(src.add(3)),
+    ];
+    let [l0, l1, l2, l3] = transpose(rows);
+    let mask = splat(MASK);
+    [
+        _mm512_and_si512(l0, mask),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<52>(l0), _mm512_slli_epi64::<12>(l1)),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<40>(l1), _mm512_slli_epi64::<24>(l2)),
+            mask,
+        ),
+        _mm512_and_si512(
+            _mm512_or_si512(_mm512_srli_epi64::<28>(l2), _mm512_slli_epi64::<36>(l3)),
+            mask,
+        ),
+        _mm512_srli_epi64::<16>(l3),
+    ]
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn store([a0, a1, a2, a3, a4]: Limbs, dst: *mut Fr) {
+    let columns = [
+        _mm512_or_si512(a0, _mm512_slli_epi64::<52>(a1)),
+        _mm512_or_si512(_mm512_srli_epi64::<12>(a1), _mm512_slli_epi64::<40>(a2)),
+        _mm512_or_si512(_mm512_srli_epi64::<24>(a2), _mm512_slli_epi64::<28>(a3)),
+        _mm512_or_si512(_mm512_srli_epi64::<36>(a3), _mm512_slli_epi64::<16>(a4)),
+    ];
+    let rows = untranspose(columns);
+    let dst = dst as *mut __m512i;
+    for (i, row) in rows.into_iter().enumerate() {
+        _mm512_storeu_si512(dst.add(i), row);
+    }
+}
+
+/// Four rows of two elements each to the columns of limbs 0 to 3 of all eight.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn transpose([r0, r1, r2, r3]: [__m512i; 4]) -> [__m512i; 4] {
+    // Limbs 0 and 1, then 2 and 3, of elements 0-3 (from r0, r1) and 4-7 (from r2, r3).
+    let even = _mm512_setr_epi64(0, 4, 8, 12, 1, 5, 9, 13);
+    let odd = _mm512_setr_epi64(2, 6, 10, 14, 3, 7, 11, 15);
+    let (x0, x1) = (
+        _mm512_permutex2var_epi64(r0, even, r1),
+        _mm512_permutex2var_epi64(r0, odd, r1),
+    );
+    let (y0, y1) = (
+        _mm512_permutex2var_epi64(r2, even, r3),
+        _mm512_permutex2var_epi64(r2, odd, r3),
+    );
+    let low = _mm512_setr_epi64(0, 1, 2, 3, 8, 9, 10, 11);
+    let high = _mm512_setr_epi64(4, 5, 6, 7, 12, 13, 14, 15);
+    [
+        _mm512_permutex2var_epi64(x0, low, y0),
+        _mm512_permutex2var_epi64(x0, high, y0),
+        _mm512_permutex2var_epi64(x1, low, y1),
+        _mm512_permutex2var_epi64(x1, high, y1),
+    ]
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn untranspose([l0, l1, l2, l3]: [__m512i; 4]) -> [__m512i; 4] {
+    let low = _mm512_setr_epi64(0, 1, 2, 3, 8, 9, 10, 11);
+    let high = _mm512_setr_epi64(4, 5, 6, 7, 12, 13, 14, 15);
+    let (x0, y0) = (
+        _mm512_permutex2var_epi64(l0, low, l1),
+        _mm512_permutex2var_epi64(l0, high, l1),
+    );
+    let (x1, y1) = (
+        _mm512_permutex2var_epi64(l2, low, l3),
+        _mm512_permutex2var_epi64(l2, high, l3),
+    );
+    let even = _mm512_setr_epi64(0, 4, 8, 12, 1, 5, 9, 13);
+    let odd = _mm512_setr_epi64(2, 6, 10, 14, 3, 7, 11, 15);
+    [
+        _mm512_permutex2var_epi64(x0, even, x1),
+        _mm512_permutex2var_epi64(x0, odd, x1),
+        _mm512_permutex2var_epi64(y0, even, y1),
+        _mm512_permutex2var_epi64(y0, odd, y1),
+    ]
+}
+
+/// x - y and a mask of the lanes where it borrowed.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn sub_borrow(x: &Limbs, y: &Limbs) -> (Limbs, __mmask8) {
+    let mask = splat(MASK);
+    let mut borrow = _mm512_setzero_si512();
+    let mut out = *x;
+    for k in 0..LIMBS {
+        let s = _mm512_sub_epi64(_mm512_sub_epi64(x[k], y[k]), borrow);
+        borrow = _mm512_srli_epi64::<63>(s);
+        out[k] = _mm512_and_si512(s, mask);
+    }
+    (out, _mm512_test_epi64_mask(borrow, borrow))
+}
+
+/// Propagates carries so every limb is below 2^52, dropping the carry out of the top limb.
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn normalize(mut x: Limbs) -> Limbs {
+    let mask = splat(MASK);
+    for k in 0..LIMBS - 1 {
+        x[k + 1] = _mm512_add_epi64(x[k + 1], _mm512_srli_epi64::<52>(x[k]));
+        x[k] = _mm512_and_si512(x[k], mask);
+    }
+    x
+}
+
+/// Maps [0, 2p) to [0, p).
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn reduce_once(x: Limbs) -> Limbs {
+    let (d, below_p) = sub_borrow(&x, &modulus());
+    let mut out = d;
+    for k in 0..LIMBS {
+        out[k] = _mm512_mask_blend_epi64(below_p, d[k], x[k]);
+    }
+    out
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn add(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut s = *a;
+    for k in 0..LIMBS {
+        s[k] = _mm512_add_epi64(a[k], b[k]);
+    }
+    reduce_once(normalize(s))
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+unsafe fn sub(a: &Limbs, b: &Limbs) -> Limbs {
+    let (d, borrowed) = sub_borrow(a, b);
+    let p = modulus();
+    let mut wrapped = d;
+    for k in 0..LIMBS {
+        wrapped[k] = _mm512_add_epi64(d[k], p[k]);
+    }
+    let wrapped = normalize(wrapped);
+    let mut out = d;
+    for k in 0..LIMBS {
+        out[k] = _mm512_mask_blend_epi64(borrowed, d[k], wrapped[k]);
+    }
+    // The wrapped sum overflows the limbs' 260 bits; the result is below 2^256.
+    out[LIMBS - 1] = _mm512_and_si512(out[LIMBS - 1], splat((1 << 48) - 1));
+    out
+}
+
+/// a * b / 2^256 mod p.
+#[inline]
+#[target_feature(enable = "avx512f,avx512ifma")]
+unsafe fn mul(a: &Limbs, b: &Limbs) -> Limbs {
+    let p = modulus();
+    let (zero, p_inv) = (_mm512_setzero_si512(), splat(P_INV & MASK));
+    let mut t = [zero; 2 * LIMBS];
+    for i in 0..LIMBS {
+        for j in 0..LIMBS {
+            t[i + j] = _mm512_madd52lo_epu64(t[i + j], a[i], b[j]);
+            t[i + j + 1] = _mm512_madd52hi_epu64(t[i + j + 1], a[i], b[j]);
+        }
+    }
+
+    // Clear the low 52 bits four times; the multiply-adds only read the low 52 bits of t[k].
+    for k in 0..LIMBS - 1 {
+        let m = _mm512_madd52lo_epu64(zero, t[k], p_inv);
+        for j in 0..LIMBS {
+            t[k + j] = _mm512_madd52lo_epu64(t[k + j], m, p[j]);
+            t[k + j + 1] = _mm512_madd52hi_epu64(t[k + j + 1], m, p[j]);
+        }
+        t[k + 1] = _mm512_add_epi64(t[k + 1], _mm512_srli_epi64::<52>(t[k]));
+    }
+    // Then the low 48 bits of t[4], for 4 * 52 + 48 = 256.
+    let m = _mm512_and_si512(
+        _mm512_madd52lo_epu64(zero, t[4], p_inv),
+        splat((1 << 48) - 1),
+    );
+    for j in 0..LIMBS {
+        t[4 + j] = _mm512_madd52lo_epu64(t[4 + j], m, p[j]);
+        t[5 + j] = _mm512_madd52hi_epu64(t[5 + j], m, p[j]);
+    }
+    let mask = splat(MASK);
+    for k in LIMBS - 1..2 * LIMBS - 1 {
+        t[k + 1] = _mm512_add_epi64(t[k + 1], _mm512_srli_epi64::<52>(t[k]));
+        t[k] = _mm512_and_si512(t[k], mask);
+    }
+
+    // Shift t[4..] right by 48 bits; the result is below 2p < 2^255.
+    let mut r = [zero; LIMBS];
+    for j in 0..LIMBS {
+        let low = _mm512_srli_epi64::<48>(t[4 + j]);
+        let high = _mm512_and_si512(_mm512_slli_epi64::<4>(t[5 + j]), mask);
+        r[j] = _mm512_or_si512(low, high);
+    }
+    reduce_once(r)
+}
+
+#[target_feature(enable = "avx512f,avx512ifma")]
+pub(super) unsafe fn elementwise(op: Op, a: *const Fr, b: *const Fr, out: *mut Fr, len: usize) {
+    let vectors = len - len % LANES;
+    for i in (0..vectors).step_by(LANES) {
+        let (x, y) = (load(a.add(i)), load(b.add(i)));
+        let z = match op {
+            Op::Add => add(&x, &y),
+            Op::Sub => sub(&x, &y),
+            Op::Mul => mul(&x, &y),
+        };
+        store(z, out.add(i));
+    }
+    scalar_tail(op, a, b, out, vectors..len);
+}
+
+/// Sums the unreduced products as ten columns of 52-bit weight per lane, plus one for carries.
+#[target_feature(enable = "avx512f,avx512ifma")]
+pub(super) unsafe fn inner_product(a: &[Fr], b: &[Fr]) -> WideAcc {
+    let (zero, mask) = (_mm512_setzero_si512(), splat(MASK));
+    let mut acc = WideAcc::ZERO;
+    let mut columns = [zero; 2 * LIMBS + 1];
+    let vectors = a.len() - a.len() % LANES;
+    for (step, i) in (0..vectors).step_by(LANES).enumerate() {
+        let (x, y) = (load(a.as_ptr().add(i)), load(b.as_ptr().add(i)));
+        for u in 0..LIMBS {
+            for v in 0..LIMBS {
+                columns[u + v] = _mm512_madd52lo_epu64(columns[u + v], x[u], y[v]);
+                columns[u + v + 1] = _mm512_madd52hi_epu64(columns[u + v + 1], x[u], y[v]);
+            }
+        }
+        if step % CARRY_EVERY == CARRY_EVERY - 1 {
+            for k in 0..2 * LIMBS {
+                columns[k + 1] =
+                    _mm512_add_epi64(columns[k + 1], _mm512_srli_epi64::<52>(columns[k]));
+                columns[k] = _mm512_and_si512(columns[k], mask);
+            }
+        }
+        if step % FLUSH_EVERY == FLUSH_EVERY - 1 {
+            flush(&mut columns, &mut acc);
+        }
+    }
+    flush(&mut columns, &mut acc);
+
+    for (x, y) in a[vectors..].iter().zip(&b[vectors..]) {
+        acc.mul_add(x, y);
+    }
+    acc
+}
+
+#[target_feature(enable = "avx512f")]
+unsafe fn flush(columns: &mut [__m512i; 2 * LIMBS + 1], acc: &mut WideAcc) {
+    let mut lanes = [0u64; LANES];
+    for (k, column) in columns.iter_mut().enumerate() {
+        _mm512_storeu_si512(lanes.as_mut_ptr() as *mut __m512i, *column);
+        for v in lanes {
+            acc.add_shifted(v, 52 * k as u32);
+        }
+        *column = _mm512_setzero_si512();
+    }
+}
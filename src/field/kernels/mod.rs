@@ -0,0 +1,318 @@
+//! Vectorized BN254 scalar-field kernels: Montgomery multiplication, add, sub and inner product
+//! over slices of `Fr`, selected at runtime from the instruction sets the CPU supports.
+//!
+//! Every kernel returns exactly what the `Fr` arithmetic would, in the same Montgomery form with
+//! R = 2^256; the vector radix is an internal detail. Lengths that do not fill a vector finish on
+//! the scalar path.
+
+#[cfg(target_arch = "x86_64")]
+mod avx2;
+#[cfg(target_arch = "x86_64")]
+mod avx512;
+#[cfg(target_arch = "aarch64")]
+mod neon;
+mod radix32;
+
+use super::lazy::{inner_product_wide, WideAcc};
+use ark_bn254::Fr;
+use ark_ff::{BigInt, PrimeField};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Forces an instruction set instead of the best one detected, e.g. `SUMCHECK_ISA=scalar`.
+pub const ISA_ENV: &str = "SUMCHECK_ISA";
+
+// The kernels read and write `Fr` as its four little-endian Montgomery limbs.
+const _: () = assert!(
+    std::mem::size_of::<Fr>() == 32
+        && std::mem::offset_of!(Fr, 0) == 0
+        && std::mem::offset_of!(BigInt<4>, 0) == 0
+);
+
+/// The modulus in 64-bit limbs, least significant first.
+const P: [u64; 4] = <Fr as PrimeField>::MODULUS.0;
+
+/// -p^-1 mod 2^64.
+const P_INV: u64 = {
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 63 {
+        inv = inv.wrapping_mul(inv).wrapping_mul(P[0]);
+        i += 1;
+    }
+    inv.wrapping_neg()
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Isa {
+    /// `Fr` arithmetic one element at a time.
+    Scalar,
+    /// Four lanes of 32-bit limbs. Without a 64-bit multiply, multiplication only keeps pace with
+    /// the scalar path; add and sub are faster.
+    Avx2,
+    /// Eight lanes of 52-bit limbs with the AVX-512 IFMA multiply-adds.
+    Avx512Ifma,
+    /// Two lanes of 32-bit limbs.
+    Neon,
+}
+
+impl std::fmt::Display for Isa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Isa {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scalar" => Ok(Isa::Scalar),
+            "avx2" => Ok(Isa::Avx2),
+            "avx512ifma" => Ok(Isa::Avx512Ifma),
+            "neon" => Ok(Isa::Neon),
+            _ => Err(format!(
+                "unknown ISA '{s}' (expected scalar, avx2, avx512ifma or neon)"
+            )),
+        }
+    }
+}
+
+impl Isa {
+    pub fn name(self) -> &'static str {
+        match self {
+            Isa::Scalar => "scalar",
+            Isa::Avx2 => "avx2",
+            Isa::Avx512Ifma => "avx512ifma",
+            Isa::Neon => "neon",
+        }
+    }
+
+    /// Whether this CPU can run the kernels.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Isa::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx512Ifma => {
+                is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512ifma")
+            }
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Every instruction set this CPU supports, fastest first.
+    pub fn supported() -> Vec<Isa> {
+        [Isa::Avx512Ifma, Isa::Avx2, Isa::Neon, Isa::Scalar]
+            .into_iter()
+            .filter(|isa| isa.is_supported())
+            .collect()
+    }
+
+    /// `$SUMCHECK_ISA` if set and supported, the fastest supported instruction set otherwise.
+    pub fn active() -> Isa {
+        static ACTIVE: OnceLock<Isa> = OnceLock::new();
+        *ACTIVE.get_or_init(|| {
+            let best = Isa::supported()[0];
+            let Ok(name) = std::env::var(ISA_ENV) else {
+                return best;
+            };
+            match name.parse::<Isa>() {
+                Ok(isa) if isa.is_supported() => isa,
+                Ok(isa) => {
+                    eprintln!("ignoring {ISA_ENV}={isa}: not supported by this CPU");
+                    best
+                }
+                Err(e) => {
+                    eprintln!("ignoring {ISA_ENV}: {e}");
+                    best
+                }
+            }
+        })
+    }
+
+    /// out = a * b
+    pub fn mul_vec(self, a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        self.elementwise(Op::Mul, a, b, out);
+    }
+
+    /// out = a + b
+    pub fn add_vec(self, a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        self.elementwise(Op::Add, a, b, out);
+    }
+
+    /// out = a - b
+    pub fn sub_vec(self, a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        self.elementwise(Op::Sub, a, b, out);
+    }
+
+    /// a *= b
+    pub fn mul_vec_inplace(self, a: &mut [Fr], b: &[Fr]) {
+        self.elementwise_inplace(Op::Mul, a, b);
+    }
+
+    /// a += b
+    pub fn add_vec_inplace(self, a: &mut [Fr], b: &[Fr]) {
+        self.elementwise_inplace(Op::Add, a, b);
+    }
+
+    /// a -= b
+    pub fn sub_vec_inplace(self, a: &mut [Fr], b: &[Fr]) {
+        self.elementwise_inplace(Op::Sub, a, b);
+    }
+
+    fn elementwise(self, op: Op, a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        assert!(a.len() == out.len() && b.len() == out.len());
+        // SAFETY: all three hold `out.len()` elements, and `a` and `b` are not `out`.
+        unsafe { self.elementwise_raw(op, a.as_ptr(), b.as_ptr(), out.as_mut_ptr(), out.len()) }
+    }
+
+    fn elementwise_inplace(self, op: Op, a: &mut [Fr], b: &[Fr]) {
+        assert_eq!(a.len(), b.len());
+        let a_ptr = a.as_mut_ptr();
+        // SAFETY: the kernels read each element of `a` before writing it.
+        unsafe { self.elementwise_raw(op, a_ptr, b.as_ptr(), a_ptr, a.len()) }
+    }
+
+    /// # Safety
+    /// `a`, `b` and `out` are valid for `len` elements; `out` may be `a` but overlaps nothing
+    /// else.
+    unsafe fn elementwise_raw(self, op: Op, a: *const Fr, b: *const Fr, out: *mut Fr, len: usize) {
+        match self {
+            // The support checks make each arm sound on CPUs without its target features.
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2 if self.is_supported() => avx2::elementwise(op, a, b, out, len),
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx512Ifma if self.is_supported() => avx512::elementwise(op, a, b, out, len),
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon if self.is_supported() => neon::elementwise(op, a, b, out, len),
+            _ => scalar_tail(op, a, b, out, 0..len),
+        }
+    }
+
+    /// sum(a[i] * b[i]), left unreduced.
+    pub fn inner_product(self, a: &[Fr], b: &[Fr]) -> WideAcc {
+        assert_eq!(a.len(), b.len());
+        match self {
+            // SAFETY: each arm runs only on CPUs that support its target features.
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx2 if self.is_supported() => unsafe { avx2::inner_product(a, b) },
+            #[cfg(target_arch = "x86_64")]
+            Isa::Avx512Ifma if self.is_supported() => unsafe { avx512::inner_product(a, b) },
+            #[cfg(target_arch = "aarch64")]
+            Isa::Neon if self.is_supported() => unsafe { neon::inner_product(a, b) },
+            _ => inner_product_wide(a, b),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Op {
+    #[inline(always)]
+    fn apply(self, a: Fr, b: Fr) -> Fr {
+        match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+        }
+    }
+}
+
+/// `op` on `Fr` over `range`, for the elements that do not fill a vector.
+#[inline(always)]
+unsafe fn scalar_tail(op: Op, a: *const Fr, b: *const Fr, out: *mut Fr, range: Range<usize>) {
+    for i in range {
+        *out.add(i) = op.apply(*a.add(i), *b.add(i));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use ark_ff::{BigInt, BigInteger, Field};
+
+    /// Random vectors plus the edge values 0, 1, p - 1 and p - 2 in both operands, at a length
+    /// that leaves a scalar tail for every lane count.
+    fn operands() -> (Vec<Fr>, Vec<Fr>) {
+        let mut a = rand_vec::<Fr>(256);
+        let mut b = a.split_off(128);
+        let mut max = Fr::MODULUS;
+        max.sub_with_borrow(&BigInt::from(1u64));
+        let max = Fr::new_unchecked(max);
+        let edges = [Fr::from(0u64), Fr::from(1u64), max, max - Fr::from(1u64)];
+        for (i, x) in edges.iter().enumerate() {
+            for (j, y) in edges.iter().enumerate() {
+                a[4 * i + j] = *x;
+                b[4 * i + j] = *y;
+            }
+        }
+        a.truncate(123);
+        b.truncate(123);
+        (a, b)
+    }
+
+    #[test]
+    fn kernels_match_fr_arithmetic() {
+        assert_eq!(P_INV.wrapping_mul(P[0]), u64::MAX);
+        let (a, b) = operands();
+        let expect = |op: fn(Fr, Fr) -> Fr| -> Vec<Fr> {
+            a.iter().zip(&b).map(|(a, b)| op(*a, *b)).collect()
+        };
+        let (mul, add, sub) = (
+            expect(|a, b| a * b),
+            expect(|a, b| a + b),
+            expect(|a, b| a - b),
+        );
+        let dot: Fr = mul.iter().sum();
+
+        for isa in Isa::supported() {
+            let mut out = vec![Fr::from(0u64); a.len()];
+            isa.mul_vec(&a, &b, &mut out);
+            assert_eq!(out, mul, "{isa} mul");
+            isa.add_vec(&a, &b, &mut out);
+            assert_eq!(out, add, "{isa} add");
+            isa.sub_vec(&a, &b, &mut out);
+            assert_eq!(out, sub, "{isa} sub");
+
+            let mut inplace = a.clone();
+            isa.mul_vec_inplace(&mut inplace, &b);
+            assert_eq!(inplace, mul, "{isa} mul in place");
+            let mut inplace = a.clone();
+            isa.add_vec_inplace(&mut inplace, &b);
+            assert_eq!(inplace, add, "{isa} add in place");
+            let mut inplace = a.clone();
+            isa.sub_vec_inplace(&mut inplace, &b);
+            assert_eq!(inplace, sub, "{isa} sub in place");
+
+            assert_eq!(
+                isa.inner_product(&a, &b).reduce(),
+                dot,
+                "{isa} inner product"
+            );
+            let squares = isa.inner_product(&a, &a).reduce();
+            assert_eq!(squares, a.iter().map(|a| a.square()).sum(), "{isa} squares");
+        }
+    }
+
+    #[test]
+    fn long_inner_products_do_not_overflow() {
+        let mut max = Fr::MODULUS;
+        max.sub_with_borrow(&BigInt::from(1u64));
+        let max = vec![Fr::new_unchecked(max); (1 << 12) + 5];
+        let expected = max[0].square() * Fr::from(max.len() as u64);
+        for isa in Isa::supported() {
+            assert_eq!(isa.inner_product(&max, &max).reduce(), expected, "{isa}");
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Two elements per 128-bit vector, on the shared radix-2^32 arithmetic.
+
+use super::radix32::{self, Lanes, Limbs};
+use super::Op;
+use crate::field::lazy::WideAcc;
+use ark_bn254::Fr;
+use std::arch::aarch64::*;
+
+impl Lanes for uint64x2_t {
+    const LANES: usize = 2;
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn splat(x: u64) -> Self {
+        vdupq_n_u64(x)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn add(self, other: Self) -> Self {
+        vaddq_u64(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn sub(self, other: Self) -> Self {
+        vsubq_u64(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn mul(self, other: Self) -> Self {
+        vmull_u32(vmovn_u64(self), vmovn_u64(other))
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn and(self, other: Self) -> Self {
+        vandq_u64(self, other)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn shr<const N: i32>(self) -> Self {
+        vshrq_n_u64::<N>(self)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn select(mask: Self, a: Self, b: Self) -> Self {
+        vbslq_u64(mask, a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn load(src: *const Fr) -> Limbs<Self> {
+        let src = src as *const u64;
+        let (e0_low, e0_high) = (vld1q_u64(src), vld1q_u64(src.add(2)));
+        let (e1_low, e1_high) = (vld1q_u64(src.add(4)), vld1q_u64(src.add(6)));
+        let columns = [
+            vtrn1q_u64(e0_low, e1_low),
+            vtrn2q_u64(e0_low, e1_low),
+            vtrn1q_u64(e0_high, e1_high),
+            vtrn2q_u64(e0_high, e1_high),
+        ];
+        let mask = vdupq_n_u64(u32::MAX as u64);
+        let mut limbs = [mask; 8];
+        for (k, column) in columns.into_iter().enumerate() {
+            limbs[2 * k] = vandq_u64(column, mask);
+            limbs[2 * k + 1] = vshrq_n_u64::<32>(column);
+        }
+        limbs
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn store(limbs: Limbs<Self>, dst: *mut Fr) {
+        let mut columns = [limbs[0]; 4];
+        for (k, column) in columns.iter_mut().enumerate() {
+            *column = vorrq_u64(limbs[2 * k], vshlq_n_u64::<32>(limbs[2 * k + 1]));
+        }
+        let dst = dst as *mut u64;
+        vst1q_u64(dst, vtrn1q_u64(columns[0], columns[1]));
+        vst1q_u64(dst.add(2), vtrn1q_u64(columns[2], columns[3]));
+        vst1q_u64(dst.add(4), vtrn2q_u64(columns[0], columns[1]));
+        vst1q_u64(dst.add(6), vtrn2q_u64(columns[2], columns[3]));
+    }
+
+    #[inline]
+    #[target_feature(enable = "neon")]
+    unsafe fn store_lanes(self, out: &mut [u64]) {
+        vst1q_u64(out[..2].as_mut_ptr(), self);
+    }
+}
+
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn elementwise(op: Op, a: *const Fr, b: *const Fr, out: *mut Fr, len: usize) {
+    radix32::elementwise::<uint64x2_t>(op, a, b, out, len)
+}
+
+#[target_feature(enable = "neon")]
+pub(super) unsafe fn inner_product(a: &[Fr], b: &[Fr]) -> WideAcc {
+    radix32::inner_product::<uint64x2_t>(a, b)
+}
@@ -0,0 +1,271 @@
+//! Montgomery arithmetic on 32-bit limbs, shared by the AVX2 and NEON kernels. Each 64-bit lane
+//! holds one element, and limb k of every lane sits in the k-th vector, so a 32x32-bit product and
+//! the carries it produces fit in the lane.
+
+use super::{scalar_tail, Op, P, P_INV};
+use crate::field::lazy::WideAcc;
+use ark_bn254::Fr;
+
+pub(super) const LIMBS: usize = 8;
+const MASK: u64 = (1 << 32) - 1;
+
+/// Inner products hand their columns to the wide accumulator after this many vectors, well before
+/// a column could overflow.
+const FLUSH_EVERY: usize = 1 << 20;
+
+pub(super) type Limbs<L> = [L; LIMBS];
+
+/// A vector of 64-bit lanes.
+pub(super) trait Lanes: Copy {
+    const LANES: usize;
+
+    unsafe fn splat(x: u64) -> Self;
+    unsafe fn add(self, other: Self) -> Self;
+    /// Wrapping.
+    unsafe fn sub(self, other: Self) -> Self;
+    /// The full 64-bit product of the low 32 bits of each lane.
+    unsafe fn mul(self, other: Self) -> Self;
+    unsafe fn and(self, other: Self) -> Self;
+    unsafe fn shr<const N: i32>(self) -> Self;
+    /// Lanes where `mask` is all ones take `a`, the others `b`.
+    unsafe fn select(mask: Self, a: Self, b: Self) -> Self;
+    /// Limbs of the `LANES` elements at `src`.
+    unsafe fn load(src: *const Fr) -> Limbs<Self>;
+    unsafe fn store(limbs: Limbs<Self>, dst: *mut Fr);
+    /// Writes the lanes to `out[..LANES]`.
+    unsafe fn store_lanes(self, out: &mut [u64]);
+}
+
+#[inline(always)]
+unsafe fn modulus<L: Lanes>() -> Limbs<L> {
+    let mut p = [L::splat(0); LIMBS];
+    for (k, limb) in p.iter_mut().enumerate() {
+        *limb = L::splat((P[k / 2] >> (32 * (k % 2))) & MASK);
+    }
+    p
+}
+
+/// Lanes where `mask` is all ones take `a`, the others `b`.
+#[inline(always)]
+unsafe fn select<L: Lanes>(mask: L, a: &Limbs<L>, b: &Limbs<L>) -> Limbs<L> {
+    let mut out = *a;
+    for k in 0..LIMBS {
+        out[k] = L::select(mask, a[k], b[k]);
+    }
+    out
+}
+
+/// x - y and the final borrow (0 or 1).
+#[inline(always)]
+unsafe fn sub_borrow<L: Lanes>(x: &Limbs<L>, y: &Limbs<L>) -> (Limbs<L>, L) {
+    let (mask, mut borrow) = (L::splat(MASK), L::splat(0));
+    let mut out = *x;
+    for k in 0..LIMBS {
+        let s = x[k].sub(y[k]).sub(borrow);
+        borrow = s.shr::<63>();
+        out[k] = s.and(mask);
+    }
+    (out, borrow)
+}
+
+/// x + y, dropping the carry out of the top limb.
+#[inline(always)]
+unsafe fn add_carry<L: Lanes>(x: &Limbs<L>, y: &Limbs<L>) -> Limbs<L> {
+    let (mask, mut carry) = (L::splat(MASK), L::splat(0));
+    let mut out = *x;
+    for k in 0..LIMBS {
+        let s = x[k].add(y[k]).add(carry);
+        carry = s.shr::<32>();
+        out[k] = s.and(mask);
+    }
+    out
+}
+
+/// Maps [0, 2p) to [0, p).
+#[inline(always)]
+unsafe fn reduce_once<L: Lanes>(x: Limbs<L>) -> Limbs<L> {
+    let (d, borrow) = sub_borrow(&x, &modulus());
+    select(L::splat(0).sub(borrow), &x, &d)
+}
+
+#[inline(always)]
+pub(super) unsafe fn add<L: Lanes>(a: &Limbs<L>, b: &Limbs<L>) -> Limbs<L> {
+    // Both are below p < 2^254, so the sum fits.
+    reduce_once(add_carry(a, b))
+}
+
+#[inline(always)]
+pub(super) unsafe fn sub<L: Lanes>(a: &Limbs<L>, b: &Limbs<L>) -> Limbs<L> {
+    let (d, borrow) = sub_borrow(a, b);
+    let wrapped = add_carry(&d, &modulus());
+    select(L::splat(0).sub(borrow), &wrapped, &d)
+}
+
+/// CIOS Montgomery multiplication with 32-bit words: a * b / 2^256 mod p, which is the Montgomery
+/// product `Fr` computes with 64-bit words.
+#[inline(always)]
+pub(super) unsafe fn mul<L: Lanes>(a: &Limbs<L>, b: &Limbs<L>) -> Limbs<L> {
+    let p = modulus::<L>();
+    let (mask, p_inv) = (L::splat(MASK), L::splat(P_INV & MASK));
+    let mut t = [L::splat(0); LIMBS + 2];
+    for b in b {
+        // At most (2^32 - 1)^2 + 2 * (2^32 - 1) < 2^64 per step.
+        let mut carry = L::splat(0);
+        for j in 0..LIMBS {
+            let s = t[j].add(a[j].mul(*b)).add(carry);
+            t[j] = s.and(mask);
+            carry = s.shr::<32>();
+        }
+        let s = t[LIMBS].add(carry);
+        t[LIMBS] = s.and(mask);
+        t[LIMBS + 1] = s.shr::<32>();
+
+        let m = t[0].mul(p_inv).and(mask);
+        let mut carry = t[0].add(m.mul(p[0])).shr::<32>();
+        for j in 1..LIMBS {
+            let s = t[j].add(m.mul(p[j])).add(carry);
+            t[j - 1] = s.and(mask);
+            carry = s.shr::<32>();
+        }
+        let s = t[LIMBS].add(carry);
+        t[LIMBS - 1] = s.and(mask);
+        t[LIMBS] = t[LIMBS + 1].add(s.shr::<32>());
+    }
+    // t < 2p < 2^255, so t[LIMBS] is zero.
+    let mut r = [L::splat(0); LIMBS];
+    r.copy_from_slice(&t[..LIMBS]);
+    reduce_once(r)
+}
+
+/// Runs `op` on `len` elements; `out` may be `a`.
+#[inline(always)]
+pub(super) unsafe fn elementwise<L: Lanes>(
+    op: Op,
+    a: *const Fr,
+    b: *const Fr,
+    out: *mut Fr,
+    len: usize,
+) {
+    let vectors = len - len % L::LANES;
+    for i in (0..vectors).step_by(L::LANES) {
+        let (x, y) = (L::load(a.add(i)), L::load(b.add(i)));
+        let z = match op {
+            Op::Add => add(&x, &y),
+            Op::Sub => sub(&x, &y),
+            Op::Mul => mul(&x, &y),
+        };
+        L::store(z, out.add(i));
+    }
+    scalar_tail(op, a, b, out, vectors..len);
+}
+
+/// Sums the unreduced products as 16 columns of 32-bit weight per lane.
+#[inline(always)]
+pub(super) unsafe fn inner_product<L: Lanes>(a: &[Fr], b: &[Fr]) -> WideAcc {
+    let mut acc = WideAcc::ZERO;
+    let mut lanes = [0u64; 8];
+    let mut flush = |columns: &mut [L; 2 * LIMBS]| {
+        for (k, column) in columns.iter_mut().enumerate() {
+            column.store_lanes(&mut lanes);
+            for v in &lanes[..L::LANES] {
+                acc.add_shifted(*v, 32 * k as u32);
+            }
+            *column = L::splat(0);
+        }
+    };
+
+    let mask = L::splat(MASK);
+    let mut columns = [L::splat(0); 2 * LIMBS];
+    let vectors = a.len() - a.len() % L::LANES;
+    for (step, i) in (0..vectors).step_by(L::LANES).enumerate() {
+        let (x, y) = (L::load(a.as_ptr().add(i)), L::load(b.as_ptr().add(i)));
+        for (u, x) in x.iter().enumerate() {
+            for (v, y) in y.iter().enumerate() {
+                let product = x.mul(*y);
+                columns[u + v] = columns[u + v].add(product.and(mask));
+                columns[u + v + 1] = columns[u + v + 1].add(product.shr::<32>());
+            }
+        }
+        if step % FLUSH_EVERY == FLUSH_EVERY - 1 {
+            flush(&mut columns);
+        }
+    }
+    flush(&mut columns);
+
+    for (x, y) in a[vectors..].iter().zip(&b[vectors..]) {
+        acc.mul_add(x, y);
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+
+    /// Two lanes in plain integers, so the NEON algorithm is also checked on other targets.
+    #[derive(Clone, Copy)]
+    struct Emulated([u64; 2]);
+
+    impl Emulated {
+        fn map(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+            Emulated([f(self.0[0], other.0[0]), f(self.0[1], other.0[1])])
+        }
+    }
+
+    impl Lanes for Emulated {
+        const LANES: usize = 2;
+
+        unsafe fn splat(x: u64) -> Self {
+            Emulated([x; 2])
+        }
+        unsafe fn add(self, other: Self) -> Self {
+            self.map(other, u64::wrapping_add)
+        }
+        unsafe fn sub(self, other: Self) -> Self {
+            self.map(other, u64::wrapping_sub)
+        }
+        unsafe fn mul(self, other: Self) -> Self {
+            self.map(other, |x, y| (x & MASK) * (y & MASK))
+        }
+        unsafe fn and(self, other: Self) -> Self {
+            self.map(other, |x, y| x & y)
+        }
+        unsafe fn shr<const N: i32>(self) -> Self {
+            self.map(self, |x, _| x >> N)
+        }
+        unsafe fn select(mask: Self, a: Self, b: Self) -> Self {
+            Emulated([0, 1].map(|i| (mask.0[i] & a.0[i]) | (!mask.0[i] & b.0[i])))
+        }
+        unsafe fn load(src: *const Fr) -> Limbs<Self> {
+            let [x, y] = [(*src).0 .0, (*src.add(1)).0 .0];
+            std::array::from_fn(|k| {
+                let shift = 32 * (k % 2);
+                Emulated([(x[k / 2] >> shift) & MASK, (y[k / 2] >> shift) & MASK])
+            })
+        }
+        unsafe fn store(limbs: Limbs<Self>, dst: *mut Fr) {
+            for lane in 0..2 {
+                let limb = |k: usize| limbs[2 * k].0[lane] | (limbs[2 * k + 1].0[lane] << 32);
+                (*dst.add(lane)).0 .0 = std::array::from_fn(limb);
+            }
+        }
+        unsafe fn store_lanes(self, out: &mut [u64]) {
+            out[..2].copy_from_slice(&self.0);
+        }
+    }
+
+    #[test]
+    fn emulated_lanes_match_fr_arithmetic() {
+        let a = rand_vec::<Fr>(64);
+        let b: Vec<Fr> = a.iter().rev().copied().collect();
+        for op in [Op::Add, Op::Sub, Op::Mul] {
+            let mut out = vec![Fr::from(0u64); 63];
+            unsafe { elementwise::<Emulated>(op, a.as_ptr(), b.as_ptr(), out.as_mut_ptr(), 63) };
+            let expected: Vec<Fr> = a.iter().zip(&b).map(|(a, b)| op.apply(*a, *b)).collect();
+            assert_eq!(out, expected[..63], "{op:?}");
+        }
+        let dot = unsafe { inner_product::<Emulated>(&a, &b) };
+        assert_eq!(dot.reduce(), a.iter().zip(&b).map(|(a, b)| *a * b).sum());
+    }
+}
@@ -0,0 +1,182 @@
+use ark_bn254::{Fq, Fr};
+use ark_ff::{Field, PrimeField};
+use std::sync::OnceLock;
+
+/// Sums of products with the reduction deferred to the end. Fields without a wide accumulator
+/// use `Self` as the accumulator and reduce every product as before.
+pub trait LazySum: Field {
+    type Acc: Copy + Send + Sync;
+
+    fn acc_zero() -> Self::Acc;
+    /// acc += a * b
+    fn mul_add(acc: &mut Self::Acc, a: &Self, b: &Self);
+    fn merge(a: Self::Acc, b: Self::Acc) -> Self::Acc;
+    fn reduce(acc: Self::Acc) -> Self;
+}
+
+/// An unreduced sum of 256x256-bit products of Montgomery-form BN254 scalars. Each product is
+/// below p^2 < 2^508, so the ninth limb leaves room for 2^68 of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WideAcc([u64; 9]);
+
+impl WideAcc {
+    pub const ZERO: WideAcc = WideAcc([0; 9]);
+
+    #[inline(always)]
+    pub fn mul_add(&mut self, a: &Fr, b: &Fr) {
+        let (a, b) = (&a.0 .0, &b.0 .0);
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                // At most (2^64 - 1)^2 + 2 * (2^64 - 1) = 2^128 - 1.
+                let t = a[i] as u128 * b[j] as u128 + product[i + j] as u128 + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        self.add_limbs(&product);
+    }
+
+    #[inline(always)]
+    fn add_limbs(&mut self, limbs: &[u64]) {
+        self.add_limbs_at(0, limbs);
+    }
+
+    #[inline(always)]
+    fn add_limbs_at(&mut self, offset: usize, limbs: &[u64]) {
+        let mut carry = false;
+        for (acc, limb) in self.0[offset..].iter_mut().zip(limbs) {
+            let (sum, c1) = acc.overflowing_add(*limb);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *acc = sum;
+            carry = c1 | c2;
+        }
+        for acc in &mut self.0[offset + limbs.len()..] {
+            let (sum, c) = acc.overflowing_add(carry as u64);
+            *acc = sum;
+            carry = c;
+        }
+        debug_assert!(!carry, "wide accumulator overflow");
+    }
+
+    /// Adds `value * 2^shift`, for sums kept as columns of a smaller radix.
+    pub fn add_shifted(&mut self, value: u64, shift: u32) {
+        let (limb, offset) = ((shift / 64) as usize, shift % 64);
+        let high = if offset == 0 {
+            0
+        } else {
+            value >> (64 - offset)
+        };
+        if high == 0 {
+            self.add_limbs_at(limb, &[value << offset]);
+        } else {
+            self.add_limbs_at(limb, &[value << offset, high]);
+        }
+    }
+
+    pub fn merge(mut self, other: WideAcc) -> WideAcc {
+        self.add_limbs(&other.0);
+        self
+    }
+
+    /// The accumulated sum is sum(aR * bR) for Montgomery radix R = 2^256, so reducing it mod p
+    /// and multiplying by R^-2 gives sum(a * b).
+    pub fn reduce(&self) -> Fr {
+        static R2_INV: OnceLock<Fr> = OnceLock::new();
+        let r2_inv = R2_INV.get_or_init(|| Fr::from(2u64).pow([512]).inverse().unwrap());
+
+        let bytes: Vec<u8> = self.0.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        Fr::from_le_bytes_mod_order(&bytes) * r2_inv
+    }
+}
+
+/// Wide `inner_product_bn254`: sum(a[i] * b[i]) without reducing the products.
+pub fn inner_product_wide(a: &[Fr], b: &[Fr]) -> WideAcc {
+    let mut acc = WideAcc::ZERO;
+    for (a, b) in a.iter().zip(b) {
+        acc.mul_add(a, b);
+    }
+    acc
+}
+
+impl LazySum for Fr {
+    type Acc = WideAcc;
+
+    fn acc_zero() -> WideAcc {
+        WideAcc::ZERO
+    }
+
+    #[inline(always)]
+    fn mul_add(acc: &mut WideAcc, a: &Fr, b: &Fr) {
+        acc.mul_add(a, b);
+    }
+
+    fn merge(a: WideAcc, b: WideAcc) -> WideAcc {
+        a.merge(b)
+    }
+
+    fn reduce(acc: WideAcc) -> Fr {
+        acc.reduce()
+    }
+}
+
+/// `LazySum` for fields without a wide accumulator: every product is reduced and added.
+macro_rules! eager_lazy_sum {
+    ($($field:ty),* $(,)?) => {$(
+        impl $crate::field::lazy::LazySum for $field {
+            type Acc = Self;
+
+            fn acc_zero() -> Self {
+                <Self as ark_std::Zero>::zero()
+            }
+
+            #[inline(always)]
+            fn mul_add(acc: &mut Self, a: &Self, b: &Self) {
+                *acc += *a * b;
+            }
+
+            fn merge(a: Self, b: Self) -> Self {
+                a + b
+            }
+
+            fn reduce(acc: Self) -> Self {
+                acc
+            }
+        }
+    )*};
+}
+
+eager_lazy_sum!(Fq);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use ark_ff::{BigInt, BigInteger};
+
+    #[test]
+    fn wide_inner_product_matches_reduced() {
+        let a = rand_vec::<Fr>(1 << 10);
+        let mut b = a.clone();
+        b.reverse();
+        let expected: Fr = a.iter().zip(&b).map(|(a, b)| *a * b).sum();
+        assert_eq!(inner_product_wide(&a, &b).reduce(), expected);
+
+        let (low, high) = a.split_at(1 << 9);
+        let merged =
+            inner_product_wide(low, &b[..1 << 9]).merge(inner_product_wide(high, &b[1 << 9..]));
+        assert_eq!(merged.reduce(), expected);
+
+        // Operands whose Montgomery limbs are p - 1, the largest there are.
+        let mut limbs = Fr::MODULUS;
+        limbs.sub_with_borrow(&BigInt::from(1u64));
+        let max = vec![Fr::new_unchecked(limbs); 1 << 10];
+        assert_eq!(
+            inner_product_wide(&max, &max).reduce(),
+            max[0] * max[0] * Fr::from(1u64 << 10)
+        );
+        assert_eq!(WideAcc::ZERO.reduce(), Fr::from(0u64));
+    }
+}
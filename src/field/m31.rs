@@ -0,0 +1,107 @@
+//! The Mersenne-31 field p = 2^31 - 1, the complex extension CM31 (i^2 = -1) and QM31 over it
+//! (u^2 = 2 + i), a degree-4 extension of M31.
+
+use super::small::{small_fp, SmallFp, SmallPrime};
+use ark_ff::{Fp2, Fp2Config, QuadExtConfig, QuadExtField, SqrtPrecomputation};
+
+pub struct M31Prime;
+
+impl SmallPrime for M31Prime {
+    const P: u64 = (1 << 31) - 1;
+    const GENERATOR: u64 = 7;
+    const TWO_ADICITY: u32 = 1;
+    const TWO_ADIC_ROOT_OF_UNITY: u64 = Self::P - 1;
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<M31>> = Some(SqrtPrecomputation::Case3Mod4 {
+        modulus_plus_one_div_four: &[1 << 29],
+    });
+
+    /// 2^64 = 4, then folds 2^31 = 1 within each word.
+    #[inline(always)]
+    fn reduce(x: u128) -> u64 {
+        let (low, high) = (x as u64, (x >> 64) as u64);
+        if high == 0 {
+            reduce64(low)
+        } else {
+            reduce64(reduce64(low) + 4 * reduce64(high))
+        }
+    }
+
+    /// The product of two values below 2^31 fits a word.
+    #[inline(always)]
+    fn mul(a: u64, b: u64) -> u64 {
+        reduce64(a * b)
+    }
+}
+
+#[inline(always)]
+fn reduce64(x: u64) -> u64 {
+    const P: u64 = M31Prime::P;
+    // Below 2^31 + 2^33, then at most p + 4.
+    let x = (x & P) + (x >> 31);
+    let x = (x & P) + (x >> 31);
+    if x >= P {
+        x - P
+    } else {
+        x
+    }
+}
+
+pub type M31 = SmallFp<M31Prime>;
+
+pub struct CM31Config;
+
+impl Fp2Config for CM31Config {
+    type Fp = M31;
+
+    const NONRESIDUE: M31 = small_fp(M31Prime::P - 1);
+
+    /// (-1)^((p^i - 1) / 2)
+    const FROBENIUS_COEFF_FP2_C1: &'static [M31] = &[small_fp(1), small_fp(M31Prime::P - 1)];
+
+    #[inline(always)]
+    fn mul_fp_by_nonresidue_in_place(fe: &mut M31) -> &mut M31 {
+        *fe = -*fe;
+        fe
+    }
+}
+
+pub type CM31 = Fp2<CM31Config>;
+
+pub struct QM31Config;
+
+impl QuadExtConfig for QM31Config {
+    type BasePrimeField = M31;
+    type BaseField = CM31;
+    type FrobCoeff = CM31;
+
+    const DEGREE_OVER_BASE_PRIME_FIELD: usize = 4;
+
+    const NONRESIDUE: CM31 = CM31::new(small_fp(2), small_fp(1));
+
+    /// (2 + i)^((p^i - 1) / 2)
+    const FROBENIUS_COEFF_C1: &'static [CM31] = &[
+        CM31::new(small_fp(1), small_fp(0)),
+        CM31::new(small_fp(21189756), small_fp(42379512)),
+        CM31::new(small_fp(M31Prime::P - 1), small_fp(0)),
+        CM31::new(small_fp(2126293891), small_fp(2105104135)),
+    ];
+
+    fn mul_base_field_by_frob_coeff(fe: &mut CM31, power: usize) {
+        *fe *= Self::FROBENIUS_COEFF_C1[power % Self::DEGREE_OVER_BASE_PRIME_FIELD];
+    }
+}
+
+pub type QM31 = QuadExtField<QM31Config>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::small::tests::{check_extension, check_prime_field};
+
+    #[test]
+    fn m31_arithmetic() {
+        check_prime_field::<M31Prime>();
+        check_extension::<CM31>();
+        check_extension::<QM31>();
+    }
+}
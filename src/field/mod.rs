@@ -0,0 +1,8 @@
+pub mod binary;
+pub mod counted;
+pub mod goldilocks;
+pub mod kernels;
+pub mod lazy;
+pub mod m31;
+pub mod small;
+pub mod vec_ops;
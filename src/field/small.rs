@@ -0,0 +1,464 @@
+//! Prime fields below 2^64 in canonical (non-Montgomery) form, and the pieces the small-field
+//! sumcheck needs from them and their extensions.
+
+use super::lazy::LazySum;
+use ark_ff::{
+    BigInt, Field, Fp, FpConfig, PrimeField, QuadExtConfig, QuadExtField, SqrtPrecomputation, Zero,
+};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// A prime p < 2^64 with a fast reduction of 128-bit integers.
+pub trait SmallPrime: 'static + Send + Sync + Sized {
+    const P: u64;
+    const GENERATOR: u64;
+    const TWO_ADICITY: u32;
+    const TWO_ADIC_ROOT_OF_UNITY: u64;
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<SmallFp<Self>>>;
+
+    /// x mod p, for any x.
+    fn reduce(x: u128) -> u64;
+
+    /// a * b mod p, for a, b < p.
+    #[inline(always)]
+    fn mul(a: u64, b: u64) -> u64 {
+        Self::reduce(a as u128 * b as u128)
+    }
+}
+
+/// `FpConfig` for a `SmallPrime`: one limb holding the canonical representative.
+pub struct Canonical<S>(PhantomData<S>);
+
+pub type SmallFp<S> = Fp<Canonical<S>, 1>;
+
+/// The element with canonical representative `x < p`.
+pub const fn small_fp<S: SmallPrime>(x: u64) -> SmallFp<S> {
+    Fp(BigInt([x]), PhantomData)
+}
+
+impl<S: SmallPrime> FpConfig<1> for Canonical<S> {
+    const MODULUS: BigInt<1> = BigInt([S::P]);
+    const GENERATOR: SmallFp<S> = small_fp(S::GENERATOR);
+    const ZERO: SmallFp<S> = small_fp(0);
+    const ONE: SmallFp<S> = small_fp(1);
+    const TWO_ADICITY: u32 = S::TWO_ADICITY;
+    const TWO_ADIC_ROOT_OF_UNITY: SmallFp<S> = small_fp(S::TWO_ADIC_ROOT_OF_UNITY);
+    const SQRT_PRECOMP: Option<SqrtPrecomputation<SmallFp<S>>> = S::SQRT_PRECOMP;
+
+    #[inline(always)]
+    fn add_assign(a: &mut SmallFp<S>, b: &SmallFp<S>) {
+        let (sum, carry) = a.0 .0[0].overflowing_add(b.0 .0[0]);
+        a.0 .0[0] = if carry || sum >= S::P {
+            sum.wrapping_sub(S::P)
+        } else {
+            sum
+        };
+    }
+
+    #[inline(always)]
+    fn sub_assign(a: &mut SmallFp<S>, b: &SmallFp<S>) {
+        let (diff, borrow) = a.0 .0[0].overflowing_sub(b.0 .0[0]);
+        a.0 .0[0] = if borrow {
+            diff.wrapping_add(S::P)
+        } else {
+            diff
+        };
+    }
+
+    #[inline(always)]
+    fn double_in_place(a: &mut SmallFp<S>) {
+        let b = *a;
+        Self::add_assign(a, &b);
+    }
+
+    #[inline(always)]
+    fn neg_in_place(a: &mut SmallFp<S>) {
+        if a.0 .0[0] != 0 {
+            a.0 .0[0] = S::P - a.0 .0[0];
+        }
+    }
+
+    #[inline(always)]
+    fn mul_assign(a: &mut SmallFp<S>, b: &SmallFp<S>) {
+        a.0 .0[0] = S::mul(a.0 .0[0], b.0 .0[0]);
+    }
+
+    fn sum_of_products<const T: usize>(a: &[SmallFp<S>; T], b: &[SmallFp<S>; T]) -> SmallFp<S> {
+        let mut acc = WideSum::ZERO;
+        for (a, b) in a.iter().zip(b) {
+            acc.mul_add(a, b);
+        }
+        acc.reduce()
+    }
+
+    #[inline(always)]
+    fn square_in_place(a: &mut SmallFp<S>) {
+        let b = *a;
+        Self::mul_assign(a, &b);
+    }
+
+    fn inverse(a: &SmallFp<S>) -> Option<SmallFp<S>> {
+        (!a.is_zero()).then(|| a.pow([S::P - 2]))
+    }
+
+    fn from_bigint(other: BigInt<1>) -> Option<SmallFp<S>> {
+        (other.0[0] < S::P).then(|| small_fp(other.0[0]))
+    }
+
+    fn into_bigint(other: SmallFp<S>) -> BigInt<1> {
+        other.0
+    }
+}
+
+/// An unreduced sum of products of canonical representatives, counting the times the 128-bit sum
+/// wrapped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WideSum {
+    sum: u128,
+    wraps: u64,
+}
+
+impl WideSum {
+    pub const ZERO: WideSum = WideSum { sum: 0, wraps: 0 };
+
+    #[inline(always)]
+    pub fn mul_add<S: SmallPrime>(&mut self, a: &SmallFp<S>, b: &SmallFp<S>) {
+        self.add(a.0 .0[0] as u128 * b.0 .0[0] as u128);
+    }
+
+    #[inline(always)]
+    fn add(&mut self, x: u128) {
+        let (sum, wrapped) = self.sum.overflowing_add(x);
+        self.sum = sum;
+        self.wraps += wrapped as u64;
+    }
+
+    pub fn merge(mut self, other: WideSum) -> WideSum {
+        self.add(other.sum);
+        self.wraps += other.wraps;
+        self
+    }
+
+    pub fn reduce<S: SmallPrime>(self) -> SmallFp<S> {
+        let p = S::P as u128;
+        let two_128 = (1u128 << 127) % p * 2 % p;
+        small_fp(S::reduce(self.sum)) + small_fp(S::reduce(self.wraps as u128 * two_128))
+    }
+}
+
+impl<S: SmallPrime> LazySum for SmallFp<S> {
+    type Acc = WideSum;
+
+    #[inline(always)]
+    fn acc_zero() -> WideSum {
+        WideSum::ZERO
+    }
+
+    #[inline(always)]
+    fn mul_add(acc: &mut WideSum, a: &Self, b: &Self) {
+        acc.mul_add(a, b);
+    }
+
+    #[inline(always)]
+    fn merge(a: WideSum, b: WideSum) -> WideSum {
+        a.merge(b)
+    }
+
+    #[inline(always)]
+    fn reduce(acc: WideSum) -> Self {
+        acc.reduce()
+    }
+}
+
+impl<P: QuadExtConfig> LazySum for QuadExtField<P> {
+    type Acc = Self;
+
+    fn acc_zero() -> Self {
+        Self::zero()
+    }
+
+    #[inline(always)]
+    fn mul_add(acc: &mut Self, a: &Self, b: &Self) {
+        *acc += *a * b;
+    }
+
+    fn merge(a: Self, b: Self) -> Self {
+        a + b
+    }
+
+    fn reduce(acc: Self) -> Self {
+        acc
+    }
+}
+
+/// Multiplication by an element of the base prime field, coordinate by coordinate instead of
+/// through the full extension multiplication.
+pub trait MulBase: Field {
+    fn mul_base(&self, x: &Self::BasePrimeField) -> Self;
+}
+
+impl<P: FpConfig<N>, const N: usize> MulBase for Fp<P, N> {
+    #[inline(always)]
+    fn mul_base(&self, x: &Self) -> Self {
+        *self * x
+    }
+}
+
+impl<P: QuadExtConfig> MulBase for QuadExtField<P>
+where
+    P::BaseField: MulBase + Field<BasePrimeField = P::BasePrimeField>,
+{
+    #[inline(always)]
+    fn mul_base(&self, x: &P::BasePrimeField) -> Self {
+        QuadExtField::new(self.c0.mul_base(x), self.c1.mul_base(x))
+    }
+}
+
+/// A `SmallFp`, for code generic over the base field that works on its canonical words.
+pub trait SmallField: PrimeField + LazySum {
+    type Prime: SmallPrime;
+
+    fn to_canonical(self) -> u64;
+    fn from_canonical(x: u64) -> Self;
+}
+
+impl<S: SmallPrime> SmallField for SmallFp<S> {
+    type Prime = S;
+
+    #[inline(always)]
+    fn to_canonical(self) -> u64 {
+        self.0 .0[0]
+    }
+
+    #[inline(always)]
+    fn from_canonical(x: u64) -> Self {
+        small_fp(x)
+    }
+}
+
+/// Elements per `PackedFp`: eight 64-bit lanes fill an AVX-512 register or two AVX2 ones.
+pub const LANES: usize = 8;
+
+/// `LANES` elements of a small field, with branch-free lane-by-lane arithmetic that the compiler
+/// keeps in vector registers.
+pub struct PackedFp<F: SmallField>([u64; LANES], PhantomData<F>);
+
+impl<F: SmallField> Clone for PackedFp<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: SmallField> Copy for PackedFp<F> {}
+
+impl<F: SmallField> PackedFp<F> {
+    pub const ZERO: Self = PackedFp([0; LANES], PhantomData);
+
+    #[inline(always)]
+    pub fn from_slice(values: &[F]) -> Self {
+        Self::from_fn(|k| values[k])
+    }
+
+    #[inline(always)]
+    pub fn from_fn(mut f: impl FnMut(usize) -> F) -> Self {
+        PackedFp(std::array::from_fn(|k| f(k).to_canonical()), PhantomData)
+    }
+
+    pub fn to_array(self) -> [F; LANES] {
+        self.0.map(F::from_canonical)
+    }
+
+    /// The sum of the lanes.
+    pub fn sum(self) -> F {
+        self.to_array().iter().sum()
+    }
+
+    #[inline(always)]
+    fn zip(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        PackedFp(
+            std::array::from_fn(|k| f(self.0[k], other.0[k])),
+            PhantomData,
+        )
+    }
+}
+
+impl<F: SmallField> Add for PackedFp<F> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        let p = F::Prime::P;
+        self.zip(other, |a, b| {
+            let (sum, carry) = a.overflowing_add(b);
+            let (reduced, borrow) = sum.overflowing_sub(p);
+            if carry || !borrow {
+                reduced
+            } else {
+                sum
+            }
+        })
+    }
+}
+
+impl<F: SmallField> AddAssign for PackedFp<F> {
+    #[inline(always)]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<F: SmallField> Sub for PackedFp<F> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        let p = F::Prime::P;
+        self.zip(other, |a, b| {
+            let (diff, borrow) = a.overflowing_sub(b);
+            if borrow {
+                diff.wrapping_add(p)
+            } else {
+                diff
+            }
+        })
+    }
+}
+
+impl<F: SmallField> Mul for PackedFp<F> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        self.zip(other, F::Prime::mul)
+    }
+}
+
+/// Lane-wise sums of products of `PackedFp`s. Below 2^32 the products fit a word and are reduced
+/// and added in the lanes; above, each lane sums its 128-bit products unreduced as `WideSum`.
+pub struct PackedSum<F: SmallField> {
+    reduced: PackedFp<F>,
+    wide: [WideSum; LANES],
+}
+
+impl<F: SmallField> Clone for PackedSum<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: SmallField> Copy for PackedSum<F> {}
+
+impl<F: SmallField> PackedSum<F> {
+    pub const ZERO: Self = PackedSum {
+        reduced: PackedFp::ZERO,
+        wide: [WideSum::ZERO; LANES],
+    };
+    const WORD_PRODUCTS: bool = F::Prime::P < 1 << 32;
+
+    /// self += a * b
+    #[inline(always)]
+    pub fn mul_add(&mut self, a: PackedFp<F>, b: PackedFp<F>) {
+        if Self::WORD_PRODUCTS {
+            self.reduced += a * b;
+        } else {
+            for ((acc, a), b) in self.wide.iter_mut().zip(a.0).zip(b.0) {
+                acc.add(a as u128 * b as u128);
+            }
+        }
+    }
+
+    pub fn merge(self, other: Self) -> Self {
+        PackedSum {
+            reduced: self.reduced + other.reduced,
+            wide: std::array::from_fn(|k| self.wide[k].merge(other.wide[k])),
+        }
+    }
+
+    /// The sum of the lanes.
+    pub fn sum(self) -> F {
+        let wide = self.wide.iter().fold(WideSum::ZERO, |acc, w| acc.merge(*w));
+        self.reduced.sum() + F::from_canonical(wide.reduce::<F::Prime>().0 .0[0])
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use ark_ff::{One, PrimeField, UniformRand};
+    use ark_std::test_rng;
+
+    /// Field operations against u128 arithmetic mod p, on random and edge values.
+    pub(crate) fn check_prime_field<S: SmallPrime>() {
+        let p = S::P as u128;
+        let mut rng = test_rng();
+        let mut values: Vec<u64> = vec![0, 1, 2, S::P - 2, S::P - 1];
+        values.extend((0..32).map(|_| SmallFp::<S>::rand(&mut rng).0 .0[0]));
+        for &x in &values {
+            let fx = SmallFp::<S>::from(x);
+            assert_eq!(fx.0 .0[0], x);
+            for &y in &values {
+                let fy = SmallFp::<S>::from(y);
+                let (x, y) = (x as u128, y as u128);
+                assert_eq!((fx + fy).0 .0[0] as u128, (x + y) % p);
+                assert_eq!((fx - fy).0 .0[0] as u128, (x + p - y) % p);
+                assert_eq!((fx * fy).0 .0[0] as u128, x * y % p);
+            }
+            assert_eq!((-fx).0 .0[0] as u128, (p - x as u128) % p);
+            if x != 0 {
+                assert!((fx * fx.inverse().unwrap()).is_one());
+            }
+            assert!((fx * fx).sqrt().is_some_and(|r| r == fx || r == -fx));
+        }
+        assert_eq!(S::reduce(u128::MAX), (u128::MAX % p) as u64);
+
+        let root = small_fp::<S>(S::TWO_ADIC_ROOT_OF_UNITY);
+        assert_eq!(root.pow([1 << (S::TWO_ADICITY - 1)]), -SmallFp::<S>::one());
+        let generator = small_fp::<S>(S::GENERATOR);
+        assert_eq!(generator.pow([S::P / 2]), -SmallFp::<S>::one());
+
+        // Enough products of p - 1 to wrap the 128-bit sum for a 64-bit p.
+        let max = SmallFp::<S>::from(S::P - 1);
+        let mut acc = WideSum::ZERO;
+        for _ in 0..1000 {
+            acc.mul_add(&max, &max);
+        }
+        assert_eq!(acc.reduce::<S>(), max * max * SmallFp::<S>::from(1000u64));
+
+        // Packed lanes against the scalar operations, edge values included.
+        let fps: Vec<SmallFp<S>> = values.iter().map(|x| SmallFp::<S>::from(*x)).collect();
+        for (x, y) in fps.chunks_exact(LANES).zip(fps.chunks_exact(LANES).skip(1)) {
+            let (px, py) = (PackedFp::from_slice(x), PackedFp::from_slice(y));
+            let lanes = |f: fn(SmallFp<S>, SmallFp<S>) -> SmallFp<S>| {
+                std::array::from_fn::<_, LANES, _>(|k| f(x[k], y[k]))
+            };
+            assert_eq!((px + py).to_array(), lanes(|x, y| x + y));
+            assert_eq!((px - py).to_array(), lanes(|x, y| x - y));
+            assert_eq!((px * py).to_array(), lanes(|x, y| x * y));
+            assert_eq!(px.sum(), x.iter().sum());
+
+            let mut acc = PackedSum::ZERO;
+            acc.mul_add(px, py);
+            acc = acc.merge(acc);
+            let products: SmallFp<S> = x.iter().zip(y).map(|(x, y)| *x * y).sum();
+            assert_eq!(acc.sum(), products.double());
+        }
+    }
+
+    pub(crate) fn check_extension<E: MulBase + UniformRand>() {
+        let mut rng = test_rng();
+        let modulus = E::BasePrimeField::MODULUS;
+        for _ in 0..8 {
+            let x = E::rand(&mut rng);
+            let mut frobenius = x;
+            frobenius.frobenius_map_in_place(1);
+            assert_eq!(frobenius, x.pow(modulus));
+            assert!((x * x.inverse().unwrap()).is_one());
+
+            let base = E::BasePrimeField::rand(&mut rng);
+            assert_eq!(x.mul_base(&base), x * E::from_base_prime_field(base));
+            let coords: Vec<_> = x.to_base_prime_field_elements().collect();
+            assert_eq!(coords.len() as u64, E::extension_degree());
+            assert_eq!(E::from_base_prime_field_elems(&coords), Some(x));
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use super::counted::Counted;
+use super::kernels::Isa;
+use super::lazy::{LazySum, WideAcc};
+use super::small::{SmallFp, SmallPrime};
+use ark_bn254::{Fq, Fr};
+use ark_ff::{Field, QuadExtConfig, QuadExtField};
+
+/// Element-wise vector arithmetic used by the SIMD kernels. The provided methods are portable
+/// loops that work for any field; fields with vectorized kernels override them.
+///
+/// All slices passed to one call have the same length.
+pub trait FieldVecOps: LazySum {
+    /// out = a + b
+    fn add_vec(a: &[Self], b: &[Self], out: &mut [Self]) {
+        portable::add_vec(a, b, out)
+    }
+    /// out = a - b
+    fn sub_vec(a: &[Self], b: &[Self], out: &mut [Self]) {
+        portable::sub_vec(a, b, out)
+    }
+    /// out = a * b
+    fn mul_vec(a: &[Self], b: &[Self], out: &mut [Self]) {
+        portable::mul_vec(a, b, out)
+    }
+    /// a += b
+    fn add_vec_inplace(a: &mut [Self], b: &[Self]) {
+        portable::add_vec_inplace(a, b)
+    }
+    /// a -= b
+    fn sub_vec_inplace(a: &mut [Self], b: &[Self]) {
+        portable::sub_vec_inplace(a, b)
+    }
+    /// a *= b
+    fn mul_vec_inplace(a: &mut [Self], b: &[Self]) {
+        portable::mul_vec_inplace(a, b)
+    }
+    /// sum(a[i] * b[i]), left unreduced.
+    fn inner_product(a: &[Self], b: &[Self]) -> Self::Acc {
+        portable::inner_product(a, b)
+    }
+}
+
+/// Scalar loops over any field, written so the compiler can unroll and vectorize them.
+pub mod portable {
+    use super::*;
+
+    pub fn add_vec<F: Field>(a: &[F], b: &[F], out: &mut [F]) {
+        for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+            *out = *a + b;
+        }
+    }
+
+    pub fn sub_vec<F: Field>(a: &[F], b: &[F], out: &mut [F]) {
+        for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+            *out = *a - b;
+        }
+    }
+
+    pub fn mul_vec<F: Field>(a: &[F], b: &[F], out: &mut [F]) {
+        for ((out, a), b) in out.iter_mut().zip(a).zip(b) {
+            *out = *a * b;
+        }
+    }
+
+    pub fn add_vec_inplace<F: Field>(a: &mut [F], b: &[F]) {
+        a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+    }
+
+    pub fn sub_vec_inplace<F: Field>(a: &mut [F], b: &[F]) {
+        a.iter_mut().zip(b).for_each(|(a, b)| *a -= b);
+    }
+
+    pub fn mul_vec_inplace<F: Field>(a: &mut [F], b: &[F]) {
+        a.iter_mut().zip(b).for_each(|(a, b)| *a *= b);
+    }
+
+    pub fn inner_product<F: LazySum>(a: &[F], b: &[F]) -> F::Acc {
+        let mut acc = F::acc_zero();
+        for (a, b) in a.iter().zip(b) {
+            F::mul_add(&mut acc, a, b);
+        }
+        acc
+    }
+}
+
+/// The in-crate vector kernels for the instruction set picked at startup.
+impl FieldVecOps for Fr {
+    fn add_vec(a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        Isa::active().add_vec(a, b, out)
+    }
+    fn sub_vec(a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        Isa::active().sub_vec(a, b, out)
+    }
+    fn mul_vec(a: &[Fr], b: &[Fr], out: &mut [Fr]) {
+        Isa::active().mul_vec(a, b, out)
+    }
+    fn add_vec_inplace(a: &mut [Fr], b: &[Fr]) {
+        Isa::active().add_vec_inplace(a, b)
+    }
+    fn sub_vec_inplace(a: &mut [Fr], b: &[Fr]) {
+        Isa::active().sub_vec_inplace(a, b)
+    }
+    fn mul_vec_inplace(a: &mut [Fr], b: &[Fr]) {
+        Isa::active().mul_vec_inplace(a, b)
+    }
+    fn inner_product(a: &[Fr], b: &[Fr]) -> WideAcc {
+        Isa::active().inner_product(a, b)
+    }
+}
+
+impl FieldVecOps for Fq {}
+
+impl<S: SmallPrime> FieldVecOps for SmallFp<S> {}
+
+impl<P: QuadExtConfig> FieldVecOps for QuadExtField<P> {}
+
+impl<F: Field> FieldVecOps for Counted<F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+
+    #[test]
+    fn bn254_kernels_match_portable() {
+        let evals = rand_vec::<Fr>(256);
+        let (a, b) = evals.split_at(128);
+        let (mut fast, mut slow) = (vec![Fr::from(0u64); 128], vec![Fr::from(0u64); 128]);
+
+        Fr::add_vec(a, b, &mut fast);
+        portable::add_vec(a, b, &mut slow);
+        assert_eq!(fast, slow);
+        Fr::sub_vec(a, b, &mut fast);
+        portable::sub_vec(a, b, &mut slow);
+        assert_eq!(fast, slow);
+        Fr::mul_vec(a, b, &mut fast);
+        portable::mul_vec(a, b, &mut slow);
+        assert_eq!(fast, slow);
+
+        Fr::add_vec_inplace(&mut fast, a);
+        portable::add_vec_inplace(&mut slow, a);
+        assert_eq!(fast, slow);
+        Fr::sub_vec_inplace(&mut fast, b);
+        portable::sub_vec_inplace(&mut slow, b);
+        assert_eq!(fast, slow);
+        Fr::mul_vec_inplace(&mut fast, a);
+        portable::mul_vec_inplace(&mut slow, a);
+        assert_eq!(fast, slow);
+
+        assert_eq!(Fr::inner_product(a, b), portable::inner_product::<Fr>(a, b));
+    }
+}
@@ -1,2 +1,7 @@
+pub mod bench;
+pub mod cli;
+pub mod field;
+pub mod machine;
+pub mod memory;
 pub mod poly;
 pub mod sumcheck;
@@ -0,0 +1,30 @@
+//! The machine a profile or baseline was recorded on; tuned parameters and timings only carry
+//! over to the same CPU model and thread count.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MachineInfo {
+    pub cpu_model: String,
+    pub available_threads: usize,
+}
+
+impl MachineInfo {
+    pub fn current() -> Self {
+        let cpu_model = std::fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split_once(':'))
+                    .map(|(_, model)| model.trim().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let available_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        MachineInfo {
+            cpu_model,
+            available_threads,
+        }
+    }
+}
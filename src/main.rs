@@ -1,5 +1,237 @@
-fn main() {
+use ark_bn254::Fr;
+use poly_bind_bench::bench::autotune::{self, TuneConfig};
+use poly_bind_bench::bench::baseline::{self, Baseline};
+use poly_bind_bench::bench::roofline::{Peaks, RooflineReport};
+use poly_bind_bench::bench::sweep::{sweep_config, SweepReport};
+use poly_bind_bench::bench::{self, parse_usize_list, BenchConfig, OutputFormat};
+use poly_bind_bench::cli::{read_file, write_file, Args};
+use poly_bind_bench::machine::MachineInfo;
+use poly_bind_bench::poly::plain::DensePolynomial;
+use poly_bind_bench::sumcheck::tuning::{self, SimdProfile};
+use poly_bind_bench::sumcheck::{cubic_claim, Backend, CubicSumcheckProof, Factor};
+use std::error::Error;
+use std::process::ExitCode;
+
+#[cfg(feature = "track-alloc")]
+#[global_allocator]
+static ALLOC: poly_bind_bench::memory::TrackingAllocator = poly_bind_bench::memory::TrackingAllocator;
+
+const USAGE: &str = "usage:
+  sumcheck prove --eq <file> --a <file> --b <file> [--backend plain|simd|interleaved|gpu] --proof <file> --claim <file>
+  sumcheck verify --eq <file> --a <file> --b <file> --proof <file> --claim <file>
+  sumcheck bench [--log-sizes 20-28] [--backends plain,simd,interleaved,gpu] [--threads 1,8]
+                 [--reps N] [--dists sequential,random,small,sparse] [--check] [--texray]
+                 [--shrink keep,fit,realloc]   (memory columns need --features track-alloc)
+                 [--sequential-below 1024] [--parallel-from 65536]   (round half-lengths)
+                 [--format table|json|csv] [--rounds] [--out <file>]
+                 [--save-baseline <file>] [--baseline <file> [--threshold 0.05] [--force]]
+  sumcheck sweep [bench flags]   (defaults: --log-sizes 16,20,24 --threads 1,2,4,..,cores)
+  sumcheck roofline [bench flags] [--stream-mib 256]   (--format table|json)
+  sumcheck tune [--threads 1,8] [--log-sizes 4-24] [--reps 5] [--profile <file>]
+                 (the SIMD backend loads the profile from $SUMCHECK_SIMD_PROFILE or
+                  ~/.config/poly-bind-bench/simd-profile.json; an empty
+                  $SUMCHECK_SIMD_PROFILE disables it)
+
+The SIMD backend's BN254 kernels use the fastest instruction set the CPU supports; set
+$SUMCHECK_ISA to scalar, avx2, avx512ifma or neon to force one.";
+
+fn main() -> ExitCode {
     tracing_texray::init();
 
-    poly_bind_bench::sumcheck::bench::main();
+    let profile = tuning::load_default_profile().unwrap_or_else(|e| {
+        eprintln!("ignoring SIMD profile: {e}");
+        None
+    });
+    tuning::set_profile(profile).expect("the SIMD profile is set before any prover runs");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("prove") => prove(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("bench") => run_bench(&args[1..]),
+        Some("sweep") => run_sweep(&args[1..]),
+        Some("roofline") => run_roofline(&args[1..]),
+        Some("tune") => run_tune(&args[1..]),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn prove(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let backend: Backend = args.parse_or("backend", Backend::Simd)?;
+
+    let eq: Vec<Fr> = read_file(args.required("eq")?)?;
+    let a: Vec<Fr> = read_file(args.required("a")?)?;
+    let b: Vec<Fr> = read_file(args.required("b")?)?;
+    if !eq.len().is_power_of_two() || a.len() != eq.len() || b.len() != eq.len() {
+        return Err(format!(
+            "eq, a and b must have the same power-of-two length (got {}, {}, {})",
+            eq.len(),
+            a.len(),
+            b.len()
+        )
+        .into());
+    }
+
+    let claim = cubic_claim(&eq, &a, &b);
+    let proof = backend.prove(eq, a, b);
+
+    write_file(args.required("proof")?, &proof)?;
+    write_file(args.required("claim")?, &claim)?;
+    Ok(())
+}
+
+fn verify(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let proof: CubicSumcheckProof = read_file(args.required("proof")?)?;
+    let claim: Fr = read_file(args.required("claim")?)?;
+
+    let num_rounds = proof.rs.len();
+
+    // The final claim is checked against eq, a and b at the challenges.
+    let mut claimed = Vec::with_capacity(3);
+    for name in ["eq", "a", "b"] {
+        let evals: Vec<Fr> = read_file(args.required(name)?)?;
+        if evals.len() != 1 << num_rounds {
+            return Err(format!(
+                "{name} has {} evaluations, the proof has {num_rounds} rounds",
+                evals.len()
+            )
+            .into());
+        }
+        claimed.push(DensePolynomial::new(evals).evaluate(&proof.rs));
+    }
+    let [eq, a, b] = [claimed[0], claimed[1], claimed[2]].map(Factor::Claimed);
+    proof.verify_factors(&claim, [eq, a, b])?;
+    println!("accepted: {num_rounds} rounds");
+    Ok(())
+}
+
+fn run_bench(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let config = BenchConfig::from_args(&args)?;
+    let format: OutputFormat = args.parse_or("format", OutputFormat::Table)?;
+    let threshold: f64 = args.parse_or("threshold", 0.05)?;
+
+    // Fail on a machine mismatch before spending time on the run.
+    let baseline = args.get("baseline").map(Baseline::load).transpose()?;
+    if let Some(baseline) = &baseline {
+        let machine = MachineInfo::current();
+        if baseline.machine != machine && !args.has("force") {
+            return Err(format!(
+                "baseline was recorded on {:?}, this machine is {:?} (pass --force to compare anyway)",
+                baseline.machine, machine
+            )
+            .into());
+        }
+    }
+
+    let report = bench::run(&config)?;
+
+    let mut out = output(&args)?;
+    match format {
+        OutputFormat::Table => report.write_table(&mut out, args.has("rounds"))?,
+        OutputFormat::Json => report.write_json(&mut out)?,
+        OutputFormat::Csv => report.write_csv(&mut out)?,
+    }
+
+    if let Some(baseline) = baseline {
+        let comparison = baseline::compare(&baseline.report, &report, threshold);
+        eprintln!(
+            "comparing against {} (threshold {:.1}%)",
+            baseline.git_revision,
+            threshold * 100.0
+        );
+        comparison.write_table(&mut std::io::stderr().lock())?;
+        let regressed = comparison.regressions().count();
+        if regressed > 0 {
+            return Err(format!("{regressed} phases regressed").into());
+        }
+    }
+
+    if let Some(path) = args.get("save-baseline") {
+        Baseline::new(report).save(path)?;
+    }
+    Ok(())
+}
+
+fn run_sweep(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let config = sweep_config(&args)?;
+    let format: OutputFormat = args.parse_or("format", OutputFormat::Table)?;
+
+    let sweep = SweepReport::new(&bench::run(&config)?);
+
+    let mut out = output(&args)?;
+    match format {
+        OutputFormat::Table => sweep.write_table(&mut out)?,
+        OutputFormat::Json => sweep.write_json(&mut out)?,
+        OutputFormat::Csv => sweep.write_csv(&mut out)?,
+    }
+    Ok(())
+}
+
+fn run_roofline(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let config = BenchConfig::from_args(&args)?;
+    let format: OutputFormat = args.parse_or("format", OutputFormat::Table)?;
+    if format == OutputFormat::Csv {
+        return Err("roofline supports --format table or json".into());
+    }
+    let stream_mib: usize = args.parse_or("stream-mib", 256)?;
+
+    let report = bench::run(&config)?;
+    let peaks = config
+        .threads
+        .iter()
+        .map(|&threads| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+            Ok(pool.install(|| Peaks::measure(stream_mib << 20)))
+        })
+        .collect::<Result<Vec<_>, rayon::ThreadPoolBuildError>>()?;
+    let roofline = RooflineReport::new(&report, peaks);
+
+    let mut out = output(&args)?;
+    match format {
+        OutputFormat::Table => roofline.write_table(&mut out)?,
+        OutputFormat::Json => roofline.write_json(&mut out)?,
+        OutputFormat::Csv => unreachable!("rejected before the run"),
+    }
+    Ok(())
+}
+
+fn run_tune(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(args)?;
+    let config = TuneConfig {
+        threads: args
+            .get("threads")
+            .map_or(Ok(vec![rayon::current_num_threads()]), parse_usize_list)?,
+        log_sizes: parse_usize_list(args.get("log-sizes").unwrap_or("4-24"))?,
+        reps: args.parse_or("reps", 5)?.max(1),
+    };
+    let path = args
+        .get("profile")
+        .map_or_else(SimdProfile::default_path, Into::into);
+
+    let (profile, points) = autotune::tune(&config)?;
+    autotune::write_table(&points, &mut std::io::stdout().lock())?;
+    profile.save(&path)?;
+    eprintln!("saved profile to {}", path.display());
+    Ok(())
+}
+
+/// `--out <file>` if given, stdout otherwise.
+fn output(args: &Args) -> Result<Box<dyn std::io::Write>, Box<dyn Error>> {
+    Ok(match args.get("out") {
+        Some(path) => Box::new(std::fs::File::create(path).map_err(|e| format!("{path}: {e}"))?),
+        None => Box::new(std::io::stdout().lock()),
+    })
 }
@@ -0,0 +1,196 @@
+use crate::poly::plain::DensePolynomial;
+use ark_ff::Field;
+use rayon::prelude::*;
+
+/// Pairs per rayon task in the transforms.
+const MIN_LEN: usize = 1 << 12;
+
+/// A multilinear polynomial in the monomial basis: the sum over S of `coeffs[S] * prod_{i in S}
+/// x_i`, with variable i in S when bit num_vars - 1 - i of S is set. Variables are ordered as in
+/// `DensePolynomial::evaluate`, so `from_evals` and `to_evals` move between the two bases.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultilinearCoeffPoly<F: Field> {
+    coeffs: Vec<F>,
+    num_vars: usize,
+}
+
+impl<F: Field> MultilinearCoeffPoly<F> {
+    pub fn new(coeffs: Vec<F>) -> Self {
+        assert!(
+            coeffs.len().is_power_of_two(),
+            "{} coefficients are not a power of two",
+            coeffs.len()
+        );
+        let num_vars = coeffs.len().ilog2() as usize;
+        MultilinearCoeffPoly { coeffs, num_vars }
+    }
+
+    /// The coefficients of the extension of `evals`, by the Möbius transform.
+    pub fn from_evals(evals: &DensePolynomial<F>) -> Self {
+        let mut coeffs = evals.Z.clone();
+        coeffs.resize(1 << evals.num_vars(), F::zero());
+        mobius_transform(&mut coeffs);
+        MultilinearCoeffPoly::new(coeffs)
+    }
+
+    /// The evaluations over the hypercube, by the zeta transform.
+    pub fn to_evals(&self) -> DensePolynomial<F> {
+        let mut evals = self.coeffs.clone();
+        zeta_transform(&mut evals);
+        DensePolynomial::new(evals)
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// `point[0]` is the top variable. p = p_0 + x_0 * p_1 with p_0 and p_1 the halves of the
+    /// coefficients, so each variable folds the halves as c_low + r * c_high.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars,
+            "point has the wrong number of variables"
+        );
+        let mut coeffs = self.coeffs.clone();
+        for r in point {
+            let half = coeffs.len() / 2;
+            let (low, high) = coeffs.split_at_mut(half);
+            low.par_iter_mut()
+                .zip(high.par_iter())
+                .with_min_len(MIN_LEN)
+                .for_each(|(l, h)| *l += *h * r);
+            coeffs.truncate(half);
+        }
+        coeffs[0]
+    }
+
+    /// The product with x_var, reduced by x_var^2 = x_var so that it stays multilinear and agrees
+    /// with the product of the evaluations over the hypercube.
+    pub fn mul_var(&self, var: usize) -> Self {
+        assert!(
+            var < self.num_vars,
+            "variable {var} out of {}",
+            self.num_vars
+        );
+        let bit = 1 << (self.num_vars - 1 - var);
+        let coeffs = (0..self.coeffs.len())
+            .into_par_iter()
+            .map(|s| match s & bit {
+                0 => F::zero(),
+                _ => self.coeffs[s] + self.coeffs[s ^ bit],
+            })
+            .collect();
+        MultilinearCoeffPoly::new(coeffs)
+    }
+
+    /// The total degree, the most variables in a monomial with a non-zero coefficient. Zero for
+    /// the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coeffs
+            .par_iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(s, _)| s.count_ones() as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The degree in variable `var`: 1 if some monomial with it has a non-zero coefficient.
+    pub fn degree_in(&self, var: usize) -> usize {
+        assert!(
+            var < self.num_vars,
+            "variable {var} out of {}",
+            self.num_vars
+        );
+        let bit = 1 << (self.num_vars - 1 - var);
+        let depends = self
+            .coeffs
+            .par_iter()
+            .enumerate()
+            .any(|(s, c)| s & bit != 0 && !c.is_zero());
+        depends as usize
+    }
+}
+
+/// Coefficients to evaluations in place: `values[S]` becomes the sum of `values[T]` over the
+/// subsets T of S.
+pub fn zeta_transform<F: Field>(values: &mut [F]) {
+    butterfly(values, |high, low| *high += low);
+}
+
+/// Evaluations to coefficients in place, the inverse of `zeta_transform`.
+pub fn mobius_transform<F: Field>(values: &mut [F]) {
+    butterfly(values, |high, low| *high -= low);
+}
+
+/// Applies `op(values[S | bit], values[S])` for each bit in turn, over every S without it.
+fn butterfly<F: Field>(values: &mut [F], op: impl Fn(&mut F, &F) + Sync) {
+    assert!(
+        values.len().is_power_of_two(),
+        "{} values are not a power of two",
+        values.len()
+    );
+    let mut half = 1;
+    while half < values.len() {
+        // Wide blocks split across tasks inside, narrow ones are grouped.
+        values
+            .par_chunks_mut(2 * half)
+            .with_min_len((MIN_LEN / half).max(1))
+            .for_each(|block| {
+                let (low, high) = block.split_at_mut(half);
+                high.par_iter_mut()
+                    .zip(low.par_iter())
+                    .with_min_len(MIN_LEN)
+                    .for_each(|(h, l)| op(h, l));
+            });
+        half *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn coeff_basis_matches_evals() {
+        for num_vars in [0, 1, 3, 8, 20] {
+            let evals = DensePolynomial::new(rand_vec::<Fr>(1 << num_vars));
+            let poly = MultilinearCoeffPoly::from_evals(&evals);
+            assert_eq!(poly.to_evals(), evals, "{num_vars}");
+            assert_eq!(poly.degree(), num_vars);
+
+            let point = rand_vec::<Fr>(num_vars);
+            assert_eq!(poly.evaluate(&point), evals.evaluate(&point), "{num_vars}");
+        }
+
+        // 1 + 2 x_0 + 3 x_0 x_1 + 4 x_2, with x_0 the top variable.
+        let coeffs = [1, 4, 0, 0, 2, 0, 3, 0].map(Fr::from).to_vec();
+        let poly = MultilinearCoeffPoly::new(coeffs);
+        let point = [5, 7, 11].map(Fr::from);
+        assert_eq!(
+            poly.evaluate(&point),
+            Fr::from(1 + 2 * 5 + 3 * 5 * 7 + 4 * 11)
+        );
+        assert_eq!(poly.degree(), 2);
+        assert_eq!([0, 1, 2].map(|v| poly.degree_in(v)), [1, 1, 1]);
+
+        // Times x_1: x_1 + 2 x_0 x_1 + 3 x_0 x_1 + 4 x_1 x_2, which is the product over the cube.
+        let product = poly.mul_var(1);
+        assert_eq!(product.coeffs(), [0, 0, 1, 4, 0, 0, 5, 0].map(Fr::from));
+        let evals = poly.to_evals();
+        let x_1 = |s: usize| Fr::from(((s >> 1) & 1) as u64);
+        let expected: Vec<Fr> = (0..8).map(|s| evals.Z[s] * x_1(s)).collect();
+        assert_eq!(product.to_evals().Z, expected);
+        assert_eq!(product.degree(), 2);
+
+        let constant = MultilinearCoeffPoly::new(vec![Fr::from(3), Fr::from(0)]);
+        assert_eq!((constant.degree(), constant.degree_in(0)), (0, 0));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::poly::plain::DensePolynomial;
+use ark_ff::Field;
+use rayon::prelude::*;
+
+/// A polynomial of degree at most `degree` in each variable, given by its evaluations over
+/// {0, ..., degree}^num_vars. The index of x is its digits in base degree + 1 with x_0, the top
+/// variable, the most significant, so degree 1 is the layout of `DensePolynomial::Z`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridPolynomial<F: Field> {
+    degree: usize,
+    num_vars: usize,
+    evals: Vec<F>,
+}
+
+impl<F: Field> GridPolynomial<F> {
+    pub fn new(degree: usize, num_vars: usize, evals: Vec<F>) -> Self {
+        assert!(degree > 0, "grid polynomials have degree at least 1");
+        assert_eq!(
+            evals.len(),
+            (degree + 1).pow(num_vars as u32),
+            "evaluations do not fill {{0..{degree}}}^{num_vars}"
+        );
+        GridPolynomial {
+            degree,
+            num_vars,
+            evals,
+        }
+    }
+
+    /// The multilinear `dense` over {0, 1}^num_vars.
+    pub fn from_dense(dense: &DensePolynomial<F>) -> Self {
+        let evals = (0..1 << dense.num_vars()).map(|i| dense.get(i)).collect();
+        GridPolynomial::new(1, dense.num_vars(), evals)
+    }
+
+    /// The pointwise product of `polys` over {0, ..., degree}^num_vars, which must be at least the
+    /// sum of their degrees.
+    pub fn product(polys: &[&GridPolynomial<F>], degree: usize) -> Self {
+        let total: usize = polys.iter().map(|p| p.degree).sum();
+        assert!(
+            degree >= total,
+            "degree {degree} is below the product's {total}"
+        );
+        let num_vars = polys.first().map_or(0, |p| p.num_vars);
+        assert!(
+            polys.iter().all(|p| p.num_vars == num_vars),
+            "factors differ in variables"
+        );
+        let extended: Vec<_> = polys.iter().map(|p| p.extend(degree)).collect();
+        let evals = (0..(degree + 1).pow(num_vars as u32))
+            .into_par_iter()
+            .map(|i| extended.iter().map(|p| p.evals[i]).product())
+            .collect();
+        GridPolynomial::new(degree, num_vars, evals)
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn evals(&self) -> &[F] {
+        &self.evals
+    }
+
+    /// The same polynomial over the larger grid {0, ..., degree}^num_vars, one variable at a
+    /// time from the bottom.
+    pub fn extend(&self, degree: usize) -> Self {
+        assert!(degree >= self.degree, "cannot shrink the grid to {degree}");
+        let (old, new) = (self.degree + 1, degree + 1);
+        let bases: Vec<Vec<F>> = (0..new)
+            .map(|m| lagrange_basis(self.degree, &F::from(m as u64)))
+            .collect();
+        let mut evals = self.evals.clone();
+        // The bottom j variables are on the new grid already, the rest on the old one.
+        for j in 0..self.num_vars {
+            let stride = new.pow(j as u32);
+            let mut next = vec![F::zero(); evals.len() / old * new];
+            next.par_chunks_mut(new * stride)
+                .zip(evals.par_chunks(old * stride))
+                .for_each(|(out, block)| {
+                    for (m, basis) in bases.iter().enumerate() {
+                        for t in 0..stride {
+                            out[m * stride + t] =
+                                (0..old).map(|k| basis[k] * block[k * stride + t]).sum();
+                        }
+                    }
+                });
+            evals = next;
+        }
+        GridPolynomial::new(degree, self.num_vars, evals)
+    }
+
+    /// Fixes the top variable to `r`, interpolating each of its rows over {0, ..., degree}.
+    pub fn bind_top(&mut self, r: &F) {
+        let basis = lagrange_basis(self.degree, r);
+        let stride = self.evals.len() / (self.degree + 1);
+        let (first, rest) = self.evals.split_at_mut(stride);
+        first.par_iter_mut().enumerate().for_each(|(t, e)| {
+            *e *= basis[0];
+            for (k, b) in basis.iter().enumerate().skip(1) {
+                *e += *b * rest[(k - 1) * stride + t];
+            }
+        });
+        self.evals.truncate(stride);
+        self.num_vars -= 1;
+    }
+
+    /// The polynomial at `point`, `point[0]` on the top variable.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars,
+            "point has the wrong number of variables"
+        );
+        let mut bound = self.clone();
+        for r in point {
+            bound.bind_top(r);
+        }
+        bound.evals[0]
+    }
+
+    /// The sum over {0, ..., width - 1}^num_vars, where width is at most degree + 1.
+    pub fn sum_over(&self, width: usize) -> F {
+        assert!(width <= self.degree + 1, "{width} points exceed the grid");
+        domain_indices(self.num_vars, width, self.degree + 1)
+            .par_iter()
+            .map(|i| self.evals[*i])
+            .sum()
+    }
+}
+
+/// The grid indices of the points of {0, ..., width - 1}^num_vars on a grid of `grid_width`
+/// points per variable, with the points in the order of their own index.
+pub fn domain_indices(num_vars: usize, width: usize, grid_width: usize) -> Vec<usize> {
+    (0..num_vars).fold(vec![0], |indices, _| {
+        indices
+            .iter()
+            .flat_map(|i| (0..width).map(move |k| i * grid_width + k))
+            .collect()
+    })
+}
+
+/// L_k(r) for k in 0..=degree, the Lagrange basis over the nodes 0, ..., degree.
+pub fn lagrange_basis<F: Field>(degree: usize, r: &F) -> Vec<F> {
+    let node = |j: usize| F::from(j as u64);
+    (0..=degree)
+        .map(|k| {
+            let (num, den) = (0..=degree)
+                .filter(|j| *j != k)
+                .fold((F::one(), F::one()), |(num, den), j| {
+                    (num * (*r - node(j)), den * (node(k) - node(j)))
+                });
+            num * den.inverse().unwrap()
+        })
+        .collect()
+}
+
+/// The univariate polynomial with `evals` at 0, ..., evals.len() - 1, at `r`.
+pub fn interpolate<F: Field>(evals: &[F], r: &F) -> F {
+    lagrange_basis(evals.len() - 1, r)
+        .iter()
+        .zip(evals)
+        .map(|(l, e)| *l * e)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn grid_matches_multilinear_products() {
+        let num_vars = 5;
+        let a = DensePolynomial::new(rand_vec::<Fr>(1 << num_vars));
+        let b = DensePolynomial::new(rand_vec::<Fr>(1 << num_vars));
+        let (ga, gb) = (
+            GridPolynomial::from_dense(&a),
+            GridPolynomial::from_dense(&b),
+        );
+        let point = rand_vec::<Fr>(num_vars);
+        assert_eq!(ga.evaluate(&point), a.evaluate(&point));
+
+        // a * b is multiquadratic and a * b * a of degree 3 in each variable.
+        let ab = GridPolynomial::product(&[&ga, &gb], 2);
+        assert_eq!(ab.evals().len(), 3usize.pow(num_vars as u32));
+        assert_eq!(ab.evaluate(&point), a.evaluate(&point) * b.evaluate(&point));
+        let aba = GridPolynomial::product(&[&ab, &ga], 3);
+        assert_eq!(aba, GridPolynomial::product(&[&ga, &gb, &ga], 3));
+        assert_eq!(ab.extend(4).evaluate(&point), ab.evaluate(&point));
+
+        let ab_sum: Fr = a.Z.iter().zip(&b.Z).map(|(x, y)| *x * y).sum();
+        assert_eq!(ab.sum_over(2), ab_sum);
+        assert_eq!(ab.sum_over(3), ab.evals().iter().sum::<Fr>());
+
+        let mut bound = ab.clone();
+        bound.bind_top(&point[0]);
+        assert_eq!(bound.evaluate(&point[1..]), ab.evaluate(&point));
+        assert_eq!(
+            interpolate(&[1, 4, 9].map(Fr::from), &Fr::from(7)),
+            Fr::from(64)
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Jagged polynomials: columns of different heights stored back to back. As a dense polynomial
+//! column c is the block of 2^row_vars entries from c * 2^row_vars, with the rows past its height
+//! zero; the packed values are that polynomial with the zeros squeezed out.
+
+use crate::poly::plain::{eq_evals, DensePolynomial};
+use crate::poly::structured::StructuredPolynomial;
+use ark_ff::Field;
+use rayon::prelude::*;
+
+/// Column offsets into the packed values, and the number of row variables of the dense form.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JaggedShape {
+    /// offsets[c]..offsets[c + 1] are the packed indices of column c.
+    offsets: Vec<usize>,
+    row_vars: usize,
+}
+
+impl JaggedShape {
+    pub fn new(heights: &[usize], row_vars: usize) -> Self {
+        assert!(!heights.is_empty(), "a jagged polynomial needs a column");
+        assert!(
+            heights.iter().all(|h| *h <= 1 << row_vars),
+            "column taller than 2^{row_vars}"
+        );
+        let offsets = std::iter::once(0)
+            .chain(heights.iter().scan(0, |end, h| {
+                *end += h;
+                Some(*end)
+            }))
+            .collect();
+        JaggedShape { offsets, row_vars }
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn height(&self, column: usize) -> usize {
+        self.offsets[column + 1] - self.offsets[column]
+    }
+
+    pub fn row_vars(&self) -> usize {
+        self.row_vars
+    }
+
+    pub fn col_vars(&self) -> usize {
+        self.num_columns().next_power_of_two().ilog2() as usize
+    }
+
+    /// The variables of the dense form, column variables on top.
+    pub fn num_vars(&self) -> usize {
+        self.col_vars() + self.row_vars
+    }
+
+    /// The number of packed values.
+    pub fn packed_len(&self) -> usize {
+        self.offsets[self.num_columns()]
+    }
+
+    /// The variables of the packed values.
+    pub fn packed_vars(&self) -> usize {
+        self.packed_len().next_power_of_two().ilog2() as usize
+    }
+
+    /// The dense index of packed index `i`.
+    pub fn dense_index(&self, i: usize) -> usize {
+        let column = self.offsets.partition_point(|offset| *offset <= i) - 1;
+        (column << self.row_vars) + i - self.offsets[column]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JaggedPolynomial<F: Field> {
+    shape: JaggedShape,
+    values: DensePolynomial<F>,
+}
+
+impl<F: Field> JaggedPolynomial<F> {
+    pub fn new(columns: &[Vec<F>], row_vars: usize) -> Self {
+        let heights: Vec<usize> = columns.iter().map(Vec::len).collect();
+        JaggedPolynomial::from_packed(JaggedShape::new(&heights, row_vars), columns.concat())
+    }
+
+    pub fn from_packed(shape: JaggedShape, values: Vec<F>) -> Self {
+        assert_eq!(
+            values.len(),
+            shape.packed_len(),
+            "values do not match the column heights"
+        );
+        let values = DensePolynomial::new_padded(values, shape.packed_vars());
+        JaggedPolynomial { shape, values }
+    }
+
+    pub fn shape(&self) -> &JaggedShape {
+        &self.shape
+    }
+
+    /// The packed values as a polynomial over `shape().packed_vars()` variables.
+    pub fn packed(&self) -> &DensePolynomial<F> {
+        &self.values
+    }
+
+    pub fn column(&self, column: usize) -> &[F] {
+        &self.values.Z[self.shape.offsets[column]..self.shape.offsets[column + 1]]
+    }
+
+    /// The dense form, up to the end of the last column.
+    pub fn to_dense(&self) -> DensePolynomial<F> {
+        let shape = &self.shape;
+        let last = shape.num_columns() - 1;
+        let len = (last << shape.row_vars) + shape.height(last);
+        let mask = (1 << shape.row_vars) - 1;
+        let z = (0..len)
+            .into_par_iter()
+            .map(|i| {
+                let (column, row) = (i >> shape.row_vars, i & mask);
+                self.column(column)
+                    .get(row)
+                    .copied()
+                    .unwrap_or_else(F::zero)
+            })
+            .collect();
+        DensePolynomial::new_padded(z, shape.num_vars())
+    }
+
+    /// The dense form's extension at `point`, from the packed values alone.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        let eq = JaggedEqPolynomial::new(self.shape.clone(), point.to_vec()).evals();
+        eq.Z.par_iter()
+            .zip(self.values.Z.par_iter())
+            .map(|(eq, v)| *eq * v)
+            .sum()
+    }
+}
+
+/// eq(point, dense_index(i)) over the packed index i: the dense eq polynomial pulled back to the
+/// packed values, so sum(eq * a * b) over the dense form is the same sum over the packed values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JaggedEqPolynomial<F: Field> {
+    shape: JaggedShape,
+    /// Over the dense variables, columns first.
+    point: Vec<F>,
+}
+
+impl<F: Field> JaggedEqPolynomial<F> {
+    pub fn new(shape: JaggedShape, point: Vec<F>) -> Self {
+        assert_eq!(
+            point.len(),
+            shape.num_vars(),
+            "point has the wrong number of variables"
+        );
+        JaggedEqPolynomial { shape, point }
+    }
+
+    pub fn shape(&self) -> &JaggedShape {
+        &self.shape
+    }
+
+    /// sum over rows r below `height` of eq(z_row, r) * eq(rho, offset + r). Runs over the bits
+    /// from the least significant, carrying the weight of each (carry of offset + r, whether r is
+    /// below height so far) state, so a column costs O(packed_vars + row_vars).
+    fn column_sum(offset: usize, height: usize, z_row: &[F], rho: &[F]) -> F {
+        let (m, p) = (z_row.len(), rho.len());
+        // state[carry][below]
+        let mut state = [[F::zero(); 2]; 2];
+        state[0][0] = F::one();
+        for j in 0..p.max(m + 1) {
+            let (t, h) = ((offset >> j) & 1, (height >> j) & 1);
+            let mut next = [[F::zero(); 2]; 2];
+            for (carry, row) in state.iter().enumerate() {
+                for (below, weight) in row.iter().enumerate() {
+                    if weight.is_zero() {
+                        continue;
+                    }
+                    // Past the row bits r is 0, and past the packed bits so is offset + r.
+                    for r in 0..if j < m { 2 } else { 1 } {
+                        let sum = t + r + carry;
+                        let z = if j < m { z_row[m - 1 - j] } else { F::zero() };
+                        let rho_j = if j < p { rho[p - 1 - j] } else { F::zero() };
+                        let eq_z = if r == 1 { z } else { F::one() - z };
+                        let eq_rho = if sum & 1 == 1 {
+                            rho_j
+                        } else {
+                            F::one() - rho_j
+                        };
+                        let below = if r == h { below } else { (r < h) as usize };
+                        next[sum >> 1][below] += *weight * eq_z * eq_rho;
+                    }
+                }
+            }
+            state = next;
+        }
+        state[0][1]
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for JaggedEqPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.shape.packed_vars()
+    }
+
+    fn evals(&self) -> DensePolynomial<F> {
+        let shape = &self.shape;
+        let (z_col, z_row) = self.point.split_at(shape.col_vars());
+        let (eq_col, eq_row) = (eq_evals(z_col), eq_evals(z_row));
+        let z = (0..shape.num_columns())
+            .into_par_iter()
+            .flat_map_iter(|c| {
+                let eq_c = eq_col[c];
+                eq_row[..shape.height(c)].iter().map(move |e| eq_c * e)
+            })
+            .collect();
+        DensePolynomial::new_padded(z, shape.packed_vars())
+    }
+
+    /// sum over columns of eq(z_col, c) * `column_sum`, in O(num_columns * num_vars).
+    fn evaluate(&self, point: &[F]) -> F {
+        let shape = &self.shape;
+        assert_eq!(
+            point.len(),
+            shape.packed_vars(),
+            "point has the wrong number of variables"
+        );
+        let (z_col, z_row) = self.point.split_at(shape.col_vars());
+        (0..shape.num_columns())
+            .into_par_iter()
+            .map(|c| {
+                let eq_col: F = z_col
+                    .iter()
+                    .enumerate()
+                    .map(|(i, z)| {
+                        let bit = (c >> (z_col.len() - 1 - i)) & 1 == 1;
+                        if bit {
+                            *z
+                        } else {
+                            F::one() - z
+                        }
+                    })
+                    .product();
+                eq_col * Self::column_sum(shape.offsets[c], shape.height(c), z_row, point)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    fn random_jagged(heights: &[usize], row_vars: usize) -> JaggedPolynomial<Fr> {
+        let mut rng = test_rng();
+        let columns: Vec<Vec<Fr>> = heights
+            .iter()
+            .map(|h| (0..*h).map(|_| Fr::rand(&mut rng)).collect())
+            .collect();
+        JaggedPolynomial::new(&columns, row_vars)
+    }
+
+    #[test]
+    fn jagged_matches_dense() {
+        let mut rng = test_rng();
+        let shapes: [(&[usize], usize); 5] = [
+            (&[5, 0, 8, 3, 1], 3),
+            (&[16, 16], 4),
+            (&[1], 0),
+            (&[2, 7, 0, 0, 4, 1, 8, 6, 3], 3),
+            (&[3, 1], 4),
+        ];
+        for (heights, row_vars) in shapes {
+            let p = random_jagged(heights, row_vars);
+            let shape = p.shape();
+            let dense = p.to_dense();
+            assert_eq!(dense.num_vars(), shape.num_vars());
+            for i in 0..shape.packed_len() {
+                assert_eq!(dense.Z[shape.dense_index(i)], p.packed().Z[i]);
+            }
+            let zeros = dense.len() - shape.packed_len();
+            assert_eq!(dense.iter().filter(|z| **z == Fr::from(0)).count(), zeros);
+
+            let point: Vec<Fr> = (0..shape.num_vars()).map(|_| Fr::rand(&mut rng)).collect();
+            assert_eq!(p.evaluate(&point), dense.evaluate(&point), "{heights:?}");
+
+            // The succinct evaluation agrees with the table, off and on the hypercube.
+            let eq = JaggedEqPolynomial::new(shape.clone(), point);
+            let evals = eq.evals();
+            let rho: Vec<Fr> = (0..shape.packed_vars())
+                .map(|_| Fr::rand(&mut rng))
+                .collect();
+            assert_eq!(eq.evaluate(&rho), evals.evaluate(&rho), "{heights:?}");
+            for i in 0..1 << shape.packed_vars() {
+                let corner: Vec<Fr> = (0..shape.packed_vars())
+                    .map(|k| Fr::from(((i >> (shape.packed_vars() - 1 - k)) & 1) as u64))
+                    .collect();
+                assert_eq!(eq.evaluate(&corner), evals.get(i));
+            }
+        }
+    }
+}
@@ -1,3 +1,10 @@
+pub mod coeff;
 #[cfg(feature = "gpu")]
 pub mod gpu;
+pub mod grid;
+pub mod jagged;
 pub mod plain;
+pub mod sparse;
+pub mod split;
+pub mod structured;
+pub mod tensor;
@@ -1,59 +1,373 @@
-use ark_ff::PrimeField;
+use ark_ff::{Field, PrimeField};
 use ark_std::test_rng;
 use rayon::prelude::*;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
+/// Evaluations over the hypercube of `num_vars` variables. `Z` may stop short of 2^num_vars
+/// entries, in which case the rest are zero.
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct DensePolynomial<F: PrimeField> {
+pub struct DensePolynomial<F: Field> {
     pub Z: Vec<F>,
+    num_vars: usize,
 }
 
-impl<F: PrimeField> DensePolynomial<F> {
+impl<F: Field> DensePolynomial<F> {
+    /// Any non-zero length, over as few variables as hold it.
     pub fn new(z: Vec<F>) -> Self {
         assert!(
-            is_power_of_two(z.len()),
-            "Dense multi-linear polynomials must be made from a power of 2 (not {})",
-            z.len()
+            !z.is_empty(),
+            "Dense multi-linear polynomials must have at least one evaluation"
         );
+        let num_vars = z.len().next_power_of_two().ilog2() as usize;
+        DensePolynomial::new_padded(z, num_vars)
+    }
 
-        DensePolynomial { Z: z }
+    /// `z` followed by zeros up to 2^num_vars.
+    pub fn new_padded(z: Vec<F>, num_vars: usize) -> Self {
+        assert!(
+            z.len() <= 1 << num_vars,
+            "{} evaluations do not fit in {num_vars} variables",
+            z.len()
+        );
+        DensePolynomial { Z: z, num_vars }
     }
 
     pub fn bound_poly_var_top(&mut self, r: &F) {
-        let n = self.Z.len() / 2;
-        let (left, right) = self.Z.split_at_mut(n);
-
-        left.par_iter_mut()
-            .zip(right.par_iter())
-            .for_each(|(a, b)| {
-                *a += *r * (*b - *a);
-            });
-
-        self.Z.truncate(n);
+        self.bound_poly_var_top_min_len(r, 1);
     }
 
     pub fn bound_poly_var_top_par(&mut self, r: &F) {
-        let n = self.Z.len() / 2;
-        let (left, right) = self.Z.split_at_mut(n);
+        self.bound_poly_var_top_min_len(r, 1);
+    }
 
-        left.par_iter_mut()
+    /// `bound_poly_var_top_par` with at least `min_len` pairs per rayon task.
+    pub fn bound_poly_var_top_min_len(&mut self, r: &F, min_len: usize) {
+        assert!(self.num_vars > 0, "no variable left to bind");
+        let n = 1 << (self.num_vars - 1);
+        // Past the stored high half, high is zero and low + r * (high - low) is low - r * low.
+        let split = n.min(self.Z.len());
+        let (left, right) = self.Z.split_at_mut(split);
+        let (paired, alone) = left.split_at_mut(right.len());
+
+        paired
+            .par_iter_mut()
             .zip(right.par_iter())
+            .with_min_len(min_len)
             .for_each(|(a, b)| {
                 *a += *r * (*b - *a);
             });
+        alone
+            .par_iter_mut()
+            .with_min_len(min_len)
+            .for_each(|a| *a -= *r * *a);
 
-        self.Z.truncate(n);
+        self.Z.truncate(split);
+        self.num_vars -= 1;
     }
 
     pub fn bound_poly_var_bot(&mut self, r: &F) {
-        let n = self.Z.len() / 2;
+        assert!(self.num_vars > 0, "no variable left to bind");
+        let n = self.Z.len().div_ceil(2);
         for i in 0..n {
-            self.Z[i] = self.Z[2 * i] + *r * (self.Z[2 * i + 1] - self.Z[2 * i]);
+            self.Z[i] = self.Z[2 * i] + *r * (self.get(2 * i + 1) - self.Z[2 * i]);
         }
         self.Z.truncate(n);
+        self.num_vars -= 1;
+    }
+
+    /// The polynomial with `Z[i] = f(i)` over `num_vars` variables.
+    pub fn from_fn(num_vars: usize, f: impl Fn(usize) -> F + Sync + Send) -> Self {
+        DensePolynomial::from_fn_padded(num_vars, 1 << num_vars, f)
+    }
+
+    /// `from_fn` for the first `len` entries only, with zeros after.
+    fn from_fn_padded(num_vars: usize, len: usize, f: impl Fn(usize) -> F + Sync + Send) -> Self {
+        DensePolynomial::new_padded((0..len).into_par_iter().map(f).collect(), num_vars)
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// The number of stored evaluations, at most 2^num_vars.
+    pub fn len(&self) -> usize {
+        self.Z.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.Z.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, F> {
+        self.Z.iter()
+    }
+
+    /// The evaluation at index `i`, zero past the stored ones.
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> F {
+        self.Z.get(i).copied().unwrap_or_else(F::zero)
+    }
+
+    /// The multilinear extension at `point`, as the inner product with the eq table of the point.
+    /// Variables are in the order `bound_poly_var_top` binds them: `point[0]` is the top one, the
+    /// most significant bit of an index into `Z`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars(),
+            "point has the wrong number of variables"
+        );
+        let eq = eq_evals(point);
+        self.Z
+            .par_iter()
+            .zip(eq.par_iter())
+            .map(|(z, eq)| *z * eq)
+            .sum()
+    }
+
+    /// Binds variable `var` (0 is the top) to `r`.
+    pub fn fix_variable(&self, var: usize, r: &F) -> Self {
+        let num_vars = self.num_vars();
+        assert!(var < num_vars, "variable {var} out of {num_vars}");
+        let stride = 1 << (num_vars - 1 - var);
+        let low = |j: usize| (j / stride) * 2 * stride + j % stride;
+        let len = live_prefix(1 << (num_vars - 1), low, self.len());
+        DensePolynomial::from_fn_padded(num_vars - 1, len, |j| {
+            let low = low(j);
+            self.Z[low] + *r * (self.get(low + stride) - self.Z[low])
+        })
+    }
+
+    /// Binds each `(var, r)` in one pass: every remaining entry is the sum of the 2^k entries it
+    /// collapses, weighted by the eq table of the values. The free variables keep their order.
+    pub fn fix_variables(&self, fixed: &[(usize, F)]) -> Self {
+        let num_vars = self.num_vars();
+        let mut fixed = fixed.to_vec();
+        fixed.sort_by_key(|(var, _)| *var);
+        assert!(
+            fixed.windows(2).all(|w| w[0].0 != w[1].0),
+            "variables fixed twice"
+        );
+        assert!(
+            fixed.iter().all(|(var, _)| *var < num_vars),
+            "variable out of {num_vars}"
+        );
+
+        let bit = |var: usize| 1usize << (num_vars - 1 - var);
+        let values: Vec<F> = fixed.iter().map(|(_, r)| *r).collect();
+        let weights = eq_evals(&values);
+        // offsets[b] sets the fixed variables to the bits of b, the first one most significant.
+        let offsets: Vec<usize> = (0..weights.len())
+            .map(|b| {
+                fixed.iter().enumerate().fold(0, |offset, (k, (var, _))| {
+                    let set = (b >> (fixed.len() - 1 - k)) & 1 == 1;
+                    offset | if set { bit(*var) } else { 0 }
+                })
+            })
+            .collect();
+        let free: Vec<usize> = (0..num_vars)
+            .filter(|var| fixed.iter().all(|(v, _)| v != var))
+            .collect();
+
+        let base = |j: usize| {
+            free.iter().enumerate().fold(0, |base, (k, var)| {
+                let set = (j >> (free.len() - 1 - k)) & 1 == 1;
+                base | if set { bit(*var) } else { 0 }
+            })
+        };
+        let len = live_prefix(1 << free.len(), base, self.len());
+        DensePolynomial::from_fn_padded(free.len(), len, |j| {
+            let base = base(j);
+            offsets
+                .iter()
+                .zip(&weights)
+                .map(|(offset, w)| self.get(base + offset) * w)
+                .sum()
+        })
+    }
+
+    /// sum(coeffs[k] * polys[k])
+    pub fn linear_combination(polys: &[&DensePolynomial<F>], coeffs: &[F]) -> Self {
+        assert_eq!(polys.len(), coeffs.len());
+        assert!(!polys.is_empty(), "no polynomials to combine");
+        let num_vars = polys[0].num_vars();
+        assert!(
+            polys.iter().all(|p| p.num_vars() == num_vars),
+            "polynomials have different numbers of variables"
+        );
+        let len = polys.iter().map(|p| p.len()).max().unwrap();
+        DensePolynomial::from_fn_padded(num_vars, len, |i| {
+            polys.iter().zip(coeffs).map(|(p, c)| p.get(i) * c).sum()
+        })
+    }
+
+    /// Applies `f` to each pair of entries up to `len`, which is where `f` of two zeros starts.
+    fn zip_with(&self, other: &Self, len: usize, f: impl Fn(F, F) -> F + Sync + Send) -> Self {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "polynomials have different numbers of variables"
+        );
+        DensePolynomial::from_fn_padded(self.num_vars, len, |i| f(self.get(i), other.get(i)))
+    }
+}
+
+/// The number of j < size with index(j) < len, for `index` increasing.
+fn live_prefix(size: usize, index: impl Fn(usize) -> usize, len: usize) -> usize {
+    let (mut lo, mut hi) = (0, size);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if index(mid) < len {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
     }
+    lo
 }
 
-impl<F: PrimeField> std::ops::Index<usize> for DensePolynomial<F> {
+/// eq(point, x) for every x in the hypercube, indexed like `DensePolynomial::Z` with `point[0]`
+/// as the most significant bit.
+pub fn eq_evals<F: Field>(point: &[F]) -> Vec<F> {
+    let mut evals = vec![F::one()];
+    for r in point {
+        let mut next = vec![F::zero(); 2 * evals.len()];
+        next.par_chunks_mut(2)
+            .zip(evals.par_iter())
+            .for_each(|(pair, e)| {
+                pair[1] = *e * r;
+                pair[0] = *e - pair[1];
+            });
+        evals = next;
+    }
+    evals
+}
+
+impl<F: Field> Add<&DensePolynomial<F>> for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn add(self, other: &DensePolynomial<F>) -> DensePolynomial<F> {
+        self.zip_with(other, self.len().max(other.len()), |a, b| a + b)
+    }
+}
+
+impl<F: Field> Sub<&DensePolynomial<F>> for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn sub(self, other: &DensePolynomial<F>) -> DensePolynomial<F> {
+        self.zip_with(other, self.len().max(other.len()), |a, b| a - b)
+    }
+}
+
+/// The entry-wise product: the multilinear extension of the product on the hypercube, not the
+/// product polynomial, which has degree 2 in each variable.
+impl<F: Field> Mul<&DensePolynomial<F>> for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn mul(self, other: &DensePolynomial<F>) -> DensePolynomial<F> {
+        self.zip_with(other, self.len().min(other.len()), |a, b| a * b)
+    }
+}
+
+impl<F: Field> Mul<F> for &DensePolynomial<F> {
+    type Output = DensePolynomial<F>;
+
+    fn mul(self, c: F) -> DensePolynomial<F> {
+        DensePolynomial {
+            Z: self.Z.par_iter().map(|z| *z * c).collect(),
+            num_vars: self.num_vars,
+        }
+    }
+}
+
+impl<F: Field> AddAssign<&DensePolynomial<F>> for DensePolynomial<F> {
+    fn add_assign(&mut self, other: &DensePolynomial<F>) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "polynomials have different numbers of variables"
+        );
+        if other.len() > self.len() {
+            self.Z.resize(other.len(), F::zero());
+        }
+        self.Z
+            .par_iter_mut()
+            .zip(other.Z.par_iter())
+            .for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<F: Field> SubAssign<&DensePolynomial<F>> for DensePolynomial<F> {
+    fn sub_assign(&mut self, other: &DensePolynomial<F>) {
+        assert_eq!(
+            self.num_vars, other.num_vars,
+            "polynomials have different numbers of variables"
+        );
+        if other.len() > self.len() {
+            self.Z.resize(other.len(), F::zero());
+        }
+        self.Z
+            .par_iter_mut()
+            .zip(other.Z.par_iter())
+            .for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<F: Field> MulAssign<F> for DensePolynomial<F> {
+    fn mul_assign(&mut self, c: F) {
+        self.Z.par_iter_mut().for_each(|z| *z *= c);
+    }
+}
+
+/// What to do with the upper half of a polynomial's buffer once a top bind has made it dead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Shrink {
+    /// Truncate only; the capacity stays at the original size for the whole sumcheck.
+    #[default]
+    Keep,
+    /// `Vec::shrink_to_fit`, which lets the allocator resize in place where it can.
+    Fit,
+    /// Copy the live half into a fresh, exactly sized buffer and free the old one.
+    Reallocate,
+}
+
+impl std::str::FromStr for Shrink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(Shrink::Keep),
+            "fit" => Ok(Shrink::Fit),
+            "realloc" => Ok(Shrink::Reallocate),
+            _ => Err(format!("unknown shrink mode '{s}' (expected keep, fit or realloc)")),
+        }
+    }
+}
+
+impl std::fmt::Display for Shrink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Shrink::Keep => "keep",
+            Shrink::Fit => "fit",
+            Shrink::Reallocate => "realloc",
+        })
+    }
+}
+
+impl Shrink {
+    /// Releases the capacity of `evals` beyond its length.
+    pub fn apply<T: Copy + Send + Sync>(self, evals: &mut Vec<T>) {
+        match self {
+            Shrink::Keep => {}
+            Shrink::Fit => evals.shrink_to_fit(),
+            Shrink::Reallocate => {
+                let mut fresh = Vec::with_capacity(evals.len());
+                evals.par_iter().copied().collect_into_vec(&mut fresh);
+                *evals = fresh;
+            }
+        }
+    }
+}
+
+impl<F: Field> std::ops::Index<usize> for DensePolynomial<F> {
     type Output = F;
 
     #[inline(always)]
@@ -63,8 +377,6 @@ impl<F: PrimeField> std::ops::Index<usize> for DensePolynomial<F> {
 }
 
 pub fn rand_vec<F: PrimeField>(n: usize) -> Vec<F> {
-    assert!(is_power_of_two(n));
-
     let mut result: Vec<F> = Vec::with_capacity(n);
     let mut rng = test_rng();
     for _ in 0..n {
@@ -83,3 +395,189 @@ pub fn rand_fr<F: PrimeField>() -> F {
 pub fn is_power_of_two(num: usize) -> bool {
     num != 0 && (num & (num - 1)) == 0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::UniformRand;
+
+    /// sum over x of Z[x] * prod_i (r_i x_i + (1 - r_i)(1 - x_i)), straight from the definition.
+    fn brute_force(z: &[Fr], point: &[Fr]) -> Fr {
+        let n = point.len();
+        (0..z.len())
+            .map(|x| {
+                point.iter().enumerate().fold(z[x], |acc, (i, r)| {
+                    let bit = (x >> (n - 1 - i)) & 1 == 1;
+                    acc * if bit { *r } else { Fr::from(1u64) - r }
+                })
+            })
+            .sum()
+    }
+
+    fn random_poly(num_vars: usize, rng: &mut impl ark_std::rand::Rng) -> DensePolynomial<Fr> {
+        DensePolynomial::new((0..1 << num_vars).map(|_| Fr::rand(rng)).collect())
+    }
+
+    fn random_point(num_vars: usize, rng: &mut impl ark_std::rand::Rng) -> Vec<Fr> {
+        (0..num_vars).map(|_| Fr::rand(rng)).collect()
+    }
+
+    #[test]
+    fn evaluate_matches_brute_force() {
+        let mut rng = test_rng();
+        for num_vars in 0..8 {
+            let p = random_poly(num_vars, &mut rng);
+            let point = random_point(num_vars, &mut rng);
+            assert_eq!(p.evaluate(&point), brute_force(&p.Z, &point), "{num_vars}");
+
+            // On the hypercube the extension is the table itself.
+            for x in [0, p.len() - 1, p.len() / 3] {
+                let corner: Vec<Fr> = (0..num_vars)
+                    .map(|i| Fr::from(((x >> (num_vars - 1 - i)) & 1) as u64))
+                    .collect();
+                assert_eq!(p.evaluate(&corner), p.Z[x]);
+            }
+            assert_eq!(eq_evals(&point).iter().sum::<Fr>(), Fr::from(1u64));
+        }
+    }
+
+    #[test]
+    fn fixing_variables_matches_brute_force() {
+        let mut rng = test_rng();
+        for num_vars in 1..8 {
+            let p = random_poly(num_vars, &mut rng);
+            let point = random_point(num_vars, &mut rng);
+            let expected = brute_force(&p.Z, &point);
+            let rest = |skip: &[usize]| -> Vec<Fr> {
+                (0..num_vars)
+                    .filter(|i| !skip.contains(i))
+                    .map(|i| point[i])
+                    .collect()
+            };
+
+            for (var, r) in point.iter().enumerate() {
+                let fixed = p.fix_variable(var, r);
+                assert_eq!(fixed.num_vars(), num_vars - 1);
+                assert_eq!(fixed.evaluate(&rest(&[var])), expected, "{num_vars}/{var}");
+            }
+            let mut top = p.clone();
+            top.bound_poly_var_top(&point[0]);
+            assert_eq!(p.fix_variable(0, &point[0]), top);
+            let mut bot = p.clone();
+            bot.bound_poly_var_bot(&point[num_vars - 1]);
+            assert_eq!(p.fix_variable(num_vars - 1, &point[num_vars - 1]), bot);
+
+            // Every other variable, given out of order, and then all of them.
+            let vars: Vec<usize> = (0..num_vars).rev().step_by(2).collect();
+            let fixed: Vec<(usize, Fr)> = vars.iter().map(|v| (*v, point[*v])).collect();
+            let once = p.fix_variables(&fixed);
+            let one_by_one = vars.iter().rev().fold(p.clone(), |q, v| {
+                let shifted = v - vars.iter().filter(|u| *u < v).count();
+                q.fix_variable(shifted, &point[*v])
+            });
+            assert_eq!(once, one_by_one);
+            assert_eq!(once.evaluate(&rest(&vars)), expected);
+
+            let all: Vec<(usize, Fr)> = point.iter().copied().enumerate().collect();
+            assert_eq!(p.fix_variables(&all).Z, vec![expected]);
+            assert_eq!(p.fix_variables(&[]), p);
+        }
+    }
+
+    #[test]
+    fn arithmetic_matches_brute_force() {
+        let mut rng = test_rng();
+        for num_vars in 0..6 {
+            let polys: Vec<DensePolynomial<Fr>> =
+                (0..3).map(|_| random_poly(num_vars, &mut rng)).collect();
+            let coeffs = random_point(3, &mut rng);
+            let point = random_point(num_vars, &mut rng);
+            let evals: Vec<Fr> = polys.iter().map(|p| brute_force(&p.Z, &point)).collect();
+            let (p, q) = (&polys[0], &polys[1]);
+
+            let refs: Vec<&DensePolynomial<Fr>> = polys.iter().collect();
+            let combined = DensePolynomial::linear_combination(&refs, &coeffs);
+            let expected: Fr = evals.iter().zip(&coeffs).map(|(e, c)| *e * c).sum();
+            assert_eq!(combined.evaluate(&point), expected);
+
+            assert_eq!((p + q).evaluate(&point), evals[0] + evals[1]);
+            assert_eq!((p - q).evaluate(&point), evals[0] - evals[1]);
+            assert_eq!((p * coeffs[0]).evaluate(&point), evals[0] * coeffs[0]);
+            let mut acc = p.clone();
+            acc += q;
+            acc -= &polys[2];
+            acc *= coeffs[1];
+            assert_eq!(
+                acc.evaluate(&point),
+                (evals[0] + evals[1] - evals[2]) * coeffs[1]
+            );
+
+            let product = p * q;
+            let pointwise: Vec<Fr> = p.iter().zip(q.iter()).map(|(a, b)| *a * b).collect();
+            assert_eq!(product.Z, pointwise);
+            assert_eq!(product.evaluate(&point), brute_force(&pointwise, &point));
+
+            let from_fn = DensePolynomial::from_fn(num_vars, |i| Fr::from(i as u64));
+            assert_eq!(from_fn.len(), 1 << num_vars);
+            assert!(from_fn
+                .iter()
+                .enumerate()
+                .all(|(i, z)| *z == Fr::from(i as u64)));
+        }
+    }
+
+    #[test]
+    fn ragged_tail_matches_padding() {
+        let mut rng = test_rng();
+        let padded = |p: &DensePolynomial<Fr>| -> Vec<Fr> {
+            let mut z = p.Z.clone();
+            z.resize(1 << p.num_vars(), Fr::from(0u64));
+            z
+        };
+        for (len, num_vars) in [(1, 0), (1, 3), (3, 2), (5, 3), (6, 4), (11, 4), (16, 4)] {
+            let p =
+                DensePolynomial::new_padded(random_poly(4, &mut rng).Z[..len].to_vec(), num_vars);
+            let full = DensePolynomial::new(padded(&p));
+            let point = random_point(num_vars, &mut rng);
+            assert_eq!(
+                p.evaluate(&point),
+                full.evaluate(&point),
+                "{len}/{num_vars}"
+            );
+            if num_vars == 0 {
+                continue;
+            }
+
+            let (mut top, mut full_top) = (p.clone(), full.clone());
+            top.bound_poly_var_top(&point[0]);
+            full_top.bound_poly_var_top(&point[0]);
+            assert!(top.len() <= p.len());
+            assert_eq!(padded(&top), full_top.Z);
+            let (mut bot, mut full_bot) = (p.clone(), full.clone());
+            bot.bound_poly_var_bot(&point[0]);
+            full_bot.bound_poly_var_bot(&point[0]);
+            assert!(bot.len() <= p.len());
+            assert_eq!(padded(&bot), full_bot.Z);
+
+            for (var, r) in point.iter().enumerate() {
+                let fixed = p.fix_variable(var, r);
+                assert!(fixed.len() <= p.len());
+                assert_eq!(padded(&fixed), full.fix_variable(var, r).Z);
+            }
+            let fixed = [(num_vars - 1, point[0])];
+            assert_eq!(
+                padded(&p.fix_variables(&fixed)),
+                full.fix_variables(&fixed).Z
+            );
+
+            let q = DensePolynomial::new_padded(random_point(1 << num_vars, &mut rng), num_vars);
+            assert_eq!(padded(&(&p + &q)), (&full + &q).Z);
+            assert_eq!(padded(&(&p * &q)), (&full * &q).Z);
+            assert_eq!((&p * &q).len(), p.len());
+            let mut sum = p.clone();
+            sum -= &q;
+            assert_eq!(padded(&sum), (&full - &q).Z);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use crate::poly::plain::DensePolynomial;
+use ark_ff::Field;
+use rayon::prelude::*;
+
+/// A multilinear polynomial given by its non-zero evaluations over the hypercube, as
+/// `(index, value)` pairs sorted by index. Indices follow `DensePolynomial::Z`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparsePolynomial<F: Field> {
+    num_vars: usize,
+    entries: Vec<(usize, F)>,
+}
+
+impl<F: Field> SparsePolynomial<F> {
+    pub fn new(num_vars: usize, entries: Vec<(usize, F)>) -> Self {
+        assert!(
+            entries.windows(2).all(|w| w[0].0 < w[1].0),
+            "sparse entries must be sorted by index without repeats"
+        );
+        assert!(
+            entries.iter().all(|(i, _)| *i < 1 << num_vars),
+            "index out of {num_vars} variables"
+        );
+        SparsePolynomial { num_vars, entries }
+    }
+
+    /// The non-zero entries of `dense`.
+    pub fn from_dense(dense: &DensePolynomial<F>) -> Self {
+        let entries = dense
+            .Z
+            .par_iter()
+            .enumerate()
+            .filter(|(_, z)| !z.is_zero())
+            .map(|(i, z)| (i, *z))
+            .collect();
+        SparsePolynomial {
+            num_vars: dense.num_vars(),
+            entries,
+        }
+    }
+
+    pub fn to_dense(&self) -> DensePolynomial<F> {
+        let len = self.entries.last().map_or(0, |(i, _)| i + 1);
+        let mut z = vec![F::zero(); len];
+        for (i, v) in &self.entries {
+            z[*i] = *v;
+        }
+        DensePolynomial::new_padded(z, self.num_vars)
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    pub fn entries(&self) -> &[(usize, F)] {
+        &self.entries
+    }
+
+    /// The number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The fraction of the hypercube that is stored.
+    pub fn density(&self) -> f64 {
+        self.entries.len() as f64 / (1u64 << self.num_vars) as f64
+    }
+
+    pub fn get(&self, i: usize) -> F {
+        match self.entries.binary_search_by_key(&i, |(j, _)| *j) {
+            Ok(k) => self.entries[k].1,
+            Err(_) => F::zero(),
+        }
+    }
+
+    /// The extension at `point`, `point[0]` on the top variable, in O(nnz * num_vars).
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars,
+            "point has the wrong number of variables"
+        );
+        let n = self.num_vars;
+        self.entries
+            .par_iter()
+            .map(|(i, v)| {
+                point.iter().enumerate().fold(*v, |acc, (k, r)| {
+                    let bit = (i >> (n - 1 - k)) & 1 == 1;
+                    acc * if bit { *r } else { F::one() - r }
+                })
+            })
+            .sum()
+    }
+
+    /// The (index, low, high) pairs of the top variable with either side non-zero, by index.
+    pub fn top_pairs(&self) -> Vec<(usize, F, F)> {
+        let half = 1 << (self.num_vars - 1);
+        let split = self.entries.partition_point(|(i, _)| *i < half);
+        let (mut low, mut high) = (
+            self.entries[..split].iter().peekable(),
+            self.entries[split..].iter().peekable(),
+        );
+        let mut pairs = Vec::with_capacity(self.entries.len());
+        loop {
+            let pair = match (low.peek(), high.peek()) {
+                (None, None) => break,
+                (Some((i, l)), Some((j, h))) if *i == j - half => {
+                    low.next();
+                    high.next();
+                    (*i, *l, *h)
+                }
+                (Some((i, l)), Some((j, _))) if *i < j - half => {
+                    low.next();
+                    (*i, *l, F::zero())
+                }
+                (Some((i, l)), None) => {
+                    low.next();
+                    (*i, *l, F::zero())
+                }
+                (_, Some((j, h))) => {
+                    high.next();
+                    (j - half, F::zero(), *h)
+                }
+            };
+            pairs.push(pair);
+        }
+        pairs
+    }
+
+    /// low + r * (high - low) over the top variable; the result has at most as many entries.
+    pub fn bind_top(&mut self, r: &F) {
+        self.entries = self
+            .top_pairs()
+            .into_iter()
+            .map(|(i, low, high)| (i, low + *r * (high - low)))
+            .collect();
+        self.num_vars -= 1;
+    }
+
+    /// low + r * (high - low) over the bottom variable, whose pairs are neighbours.
+    pub fn bind_bot(&mut self, r: &F) {
+        let mut entries: Vec<(usize, F)> = Vec::with_capacity(self.entries.len());
+        let mut k = 0;
+        while k < self.entries.len() {
+            let (i, v) = self.entries[k];
+            let (low, high) = if i & 1 == 1 {
+                (F::zero(), v)
+            } else if k + 1 < self.entries.len() && self.entries[k + 1].0 == i + 1 {
+                k += 1;
+                (v, self.entries[k].1)
+            } else {
+                (v, F::zero())
+            };
+            entries.push((i >> 1, low + *r * (high - low)));
+            k += 1;
+        }
+        self.entries = entries;
+        self.num_vars -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ff::Zero;
+    use ark_std::rand::Rng;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn sparse_matches_dense() {
+        let mut rng = test_rng();
+        for (num_vars, nnz) in [(1, 1), (4, 3), (6, 20), (8, 5), (8, 256)] {
+            let mut indices: Vec<usize> =
+                (0..nnz).map(|_| rng.gen_range(0..1 << num_vars)).collect();
+            indices.sort();
+            indices.dedup();
+            let entries = indices.iter().map(|i| (*i, Fr::rand(&mut rng))).collect();
+            let sparse = SparsePolynomial::new(num_vars, entries);
+            let dense = sparse.to_dense();
+            assert_eq!(SparsePolynomial::from_dense(&dense), sparse);
+            for i in 0..1 << num_vars {
+                assert_eq!(sparse.get(i), dense.get(i));
+            }
+
+            let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+            assert_eq!(sparse.evaluate(&point), dense.evaluate(&point));
+
+            let (mut top, mut dense_top) = (sparse.clone(), dense.clone());
+            top.bind_top(&point[0]);
+            dense_top.bound_poly_var_top(&point[0]);
+            assert!(top.nnz() <= sparse.nnz());
+            assert_eq!(top.to_dense().Z, dense_top.Z[..top.to_dense().len()]);
+            assert!(dense_top.Z[top.to_dense().len()..]
+                .iter()
+                .all(|z| z.is_zero()));
+
+            let (mut bot, mut dense_bot) = (sparse.clone(), dense.clone());
+            bot.bind_bot(&point[0]);
+            dense_bot.bound_poly_var_bot(&point[0]);
+            assert!(bot.nnz() <= sparse.nnz());
+            assert_eq!(bot.to_dense().Z, dense_bot.Z[..bot.to_dense().len()]);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use crate::poly::plain::DensePolynomial;
+use ark_ff::Field;
+
+/// f[x] = high[x >> low_vars] * low[x & (2^low_vars - 1)], two tables stored in full over halves
+/// of the variables. Binding the top variable folds `high`, then `low` once `high` is a single
+/// value, so a round reads them as they are and never rebuilds them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitTables<F: Field> {
+    high: DensePolynomial<F>,
+    low: DensePolynomial<F>,
+}
+
+impl<F: Field> SplitTables<F> {
+    pub fn new(high: Vec<F>, low: Vec<F>) -> Self {
+        let (high, low) = (DensePolynomial::new(high), DensePolynomial::new(low));
+        assert!(
+            high.len() == 1 << high.num_vars() && low.len() == 1 << low.num_vars(),
+            "split tables must be powers of two"
+        );
+        SplitTables { high, low }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.high.num_vars() + self.low.num_vars()
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize) -> F {
+        let low_vars = self.low.num_vars();
+        self.high.Z[x >> low_vars] * self.low.Z[x & ((1 << low_vars) - 1)]
+    }
+
+    pub fn bind_top(&mut self, r: &F) {
+        match self.high.num_vars() {
+            0 => self.low.bound_poly_var_top(r),
+            _ => self.high.bound_poly_var_top(r),
+        }
+    }
+
+    pub fn to_dense(&self) -> DensePolynomial<F> {
+        DensePolynomial::from_fn(self.num_vars(), |x| self.get(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use ark_bn254::Fr;
+
+    #[test]
+    fn binds_like_the_dense_product() {
+        let (high, low) = (rand_vec::<Fr>(8), rand_vec::<Fr>(4));
+        let mut tables = SplitTables::new(high.clone(), low.clone());
+        let mut dense = DensePolynomial::from_fn(5, |x| high[x >> 2] * low[x & 3]);
+        assert_eq!(tables.to_dense(), dense);
+        for r in rand_vec::<Fr>(5) {
+            tables.bind_top(&r);
+            dense.bound_poly_var_top(&r);
+            assert_eq!(tables.num_vars(), dense.num_vars());
+            assert_eq!(tables.to_dense(), dense);
+        }
+    }
+}
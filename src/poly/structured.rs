@@ -0,0 +1,299 @@
+//! Polynomials with a closed form, so a verifier evaluates their multilinear extension in O(n)
+//! instead of receiving an opaque table. Points follow `DensePolynomial::evaluate`: `point[0]`
+//! is the top variable, the most significant bit of an index.
+
+use crate::poly::plain::{eq_evals, DensePolynomial};
+use ark_ff::Field;
+use rayon::prelude::*;
+
+pub trait StructuredPolynomial<F: Field>: Sync {
+    fn num_vars(&self) -> usize;
+
+    /// The table over the hypercube.
+    fn evals(&self) -> DensePolynomial<F>;
+
+    /// The multilinear extension at `point`, in O(num_vars).
+    fn evaluate(&self, point: &[F]) -> F;
+}
+
+/// eq(a, b) for one variable.
+fn eq1<F: Field>(a: &F, b: &F) -> F {
+    *a * b + (F::one() - a) * (F::one() - b)
+}
+
+fn check_len(point: &[impl Sized], num_vars: usize) {
+    assert_eq!(
+        point.len(),
+        num_vars,
+        "point has the wrong number of variables"
+    );
+}
+
+/// The table of prod_i (x_i ? high_i : low_i), `factors[0]` on the top variable.
+fn product_evals<F: Field>(factors: &[(F, F)]) -> Vec<F> {
+    let mut evals = vec![F::one()];
+    for (low, high) in factors {
+        let mut next = vec![F::zero(); 2 * evals.len()];
+        next.par_chunks_mut(2)
+            .zip(evals.par_iter())
+            .for_each(|(pair, e)| {
+                pair[0] = *e * low;
+                pair[1] = *e * high;
+            });
+        evals = next;
+    }
+    evals
+}
+
+/// eq(r, x) = prod_i (r_i x_i + (1 - r_i)(1 - x_i)).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EqPolynomial<F: Field> {
+    r: Vec<F>,
+}
+
+impl<F: Field> EqPolynomial<F> {
+    pub fn new(r: Vec<F>) -> Self {
+        EqPolynomial { r }
+    }
+
+    pub fn point(&self) -> &[F] {
+        &self.r
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for EqPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.r.len()
+    }
+
+    fn evals(&self) -> DensePolynomial<F> {
+        DensePolynomial::new(eq_evals(&self.r))
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        check_len(point, self.r.len());
+        self.r.iter().zip(point).map(|(r, x)| eq1(r, x)).product()
+    }
+}
+
+/// 1 where x = r + 1 as integers on the hypercube, with no wrap-around at the top.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EqPlusOnePolynomial<F: Field> {
+    r: Vec<F>,
+}
+
+impl<F: Field> EqPlusOnePolynomial<F> {
+    pub fn new(r: Vec<F>) -> Self {
+        EqPlusOnePolynomial { r }
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for EqPlusOnePolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.r.len()
+    }
+
+    /// eq(r, x - 1), shifted up by one entry.
+    fn evals(&self) -> DensePolynomial<F> {
+        let mut evals = eq_evals(&self.r);
+        evals.pop();
+        evals.insert(0, F::zero());
+        DensePolynomial::new(evals)
+    }
+
+    /// x = r + 1 when, for some k, the k low bits of r are 1 and those of x are 0, bit k goes
+    /// from 0 in r to 1 in x, and the bits above agree.
+    fn evaluate(&self, point: &[F]) -> F {
+        let n = self.r.len();
+        check_len(point, n);
+        // prefix[i]: eq over the top i variables.
+        let mut prefix = Vec::with_capacity(n + 1);
+        prefix.push(F::one());
+        for (r, x) in self.r.iter().zip(point) {
+            prefix.push(*prefix.last().unwrap() * eq1(r, x));
+        }
+
+        let mut carry = F::one();
+        let mut sum = F::zero();
+        for i in (0..n).rev() {
+            let (r, x) = (self.r[i], point[i]);
+            sum += prefix[i] * (F::one() - r) * x * carry;
+            carry *= r * (F::one() - x);
+        }
+        sum
+    }
+}
+
+/// [x < r] as integers on the hypercube.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LtPolynomial<F: Field> {
+    r: Vec<F>,
+}
+
+impl<F: Field> LtPolynomial<F> {
+    pub fn new(r: Vec<F>) -> Self {
+        LtPolynomial { r }
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for LtPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.r.len()
+    }
+
+    /// One variable at a time, keeping eq over the variables so far alongside lt: x drops
+    /// below r at the first variable where x_i = 0 and r_i = 1.
+    fn evals(&self) -> DensePolynomial<F> {
+        let (mut eq, mut lt) = (vec![F::one()], vec![F::zero()]);
+        for r in &self.r {
+            let mut next_eq = vec![F::zero(); 2 * eq.len()];
+            let mut next_lt = vec![F::zero(); 2 * lt.len()];
+            next_eq
+                .par_chunks_mut(2)
+                .zip(next_lt.par_chunks_mut(2))
+                .zip(eq.par_iter().zip(lt.par_iter()))
+                .for_each(|((eq_pair, lt_pair), (e, l))| {
+                    eq_pair[1] = *e * r;
+                    eq_pair[0] = *e - eq_pair[1];
+                    lt_pair[0] = *l + eq_pair[1];
+                    lt_pair[1] = *l;
+                });
+            (eq, lt) = (next_eq, next_lt);
+        }
+        DensePolynomial::new(lt)
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        check_len(point, self.r.len());
+        let mut eq = F::one();
+        let mut lt = F::zero();
+        for (r, x) in self.r.iter().zip(point) {
+            lt += eq * (F::one() - x) * r;
+            eq *= eq1(r, x);
+        }
+        lt
+    }
+}
+
+/// The index itself: x as an integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IdentityPolynomial {
+    num_vars: usize,
+}
+
+impl IdentityPolynomial {
+    pub fn new(num_vars: usize) -> Self {
+        IdentityPolynomial { num_vars }
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for IdentityPolynomial {
+    fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    fn evals(&self) -> DensePolynomial<F> {
+        DensePolynomial::from_fn(self.num_vars, |i| F::from(i as u64))
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        check_len(point, self.num_vars);
+        point.iter().fold(F::zero(), |acc, x| acc.double() + x)
+    }
+}
+
+/// base^x for x as an integer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowersPolynomial<F: Field> {
+    /// base^(2^(n - 1 - i)), the factor of variable i.
+    squares: Vec<F>,
+}
+
+impl<F: Field> PowersPolynomial<F> {
+    pub fn new(base: F, num_vars: usize) -> Self {
+        let mut squares: Vec<F> = std::iter::successors(Some(base), |b| Some(b.square()))
+            .take(num_vars)
+            .collect();
+        squares.reverse();
+        PowersPolynomial { squares }
+    }
+}
+
+impl<F: Field> StructuredPolynomial<F> for PowersPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.squares.len()
+    }
+
+    fn evals(&self) -> DensePolynomial<F> {
+        let factors: Vec<(F, F)> = self.squares.iter().map(|c| (F::one(), *c)).collect();
+        DensePolynomial::new(product_evals(&factors))
+    }
+
+    fn evaluate(&self, point: &[F]) -> F {
+        check_len(point, self.squares.len());
+        self.squares
+            .iter()
+            .zip(point)
+            .map(|(c, x)| F::one() + *x * (*c - F::one()))
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    fn bits(x: usize, num_vars: usize) -> Vec<Fr> {
+        (0..num_vars)
+            .map(|i| Fr::from(((x >> (num_vars - 1 - i)) & 1) as u64))
+            .collect()
+    }
+
+    /// evaluate agrees with the extension of evals, both off and on the hypercube.
+    fn check_evaluate(p: &dyn StructuredPolynomial<Fr>, rng: &mut impl ark_std::rand::Rng) {
+        let n = p.num_vars();
+        let evals = p.evals();
+        assert_eq!(evals.num_vars(), n);
+        let point: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+        assert_eq!(p.evaluate(&point), evals.evaluate(&point));
+        for x in 0..evals.len() {
+            assert_eq!(p.evaluate(&bits(x, n)), evals[x]);
+        }
+    }
+
+    #[test]
+    fn structured_polynomials() {
+        let mut rng = test_rng();
+        for n in 0..7 {
+            let size = 1usize << n;
+            let r = (size * 5 / 8).min(size - 1);
+            let point = bits(r, n);
+            let base = Fr::from(3u64);
+
+            let eq = EqPolynomial::new(point.clone());
+            let next = EqPlusOnePolynomial::new(point.clone());
+            let lt = LtPolynomial::new(point.clone());
+            let powers = PowersPolynomial::new(base, n);
+            let polys: [&dyn StructuredPolynomial<Fr>; 5] =
+                [&eq, &next, &lt, &IdentityPolynomial::new(n), &powers];
+            let expected: [&dyn Fn(usize) -> Fr; 5] = [
+                &|x| Fr::from((x == r) as u64),
+                &|x| Fr::from((x == r + 1) as u64),
+                &|x| Fr::from((x < r) as u64),
+                &|x| Fr::from(x as u64),
+                &|x| base.pow([x as u64]),
+            ];
+            for (p, f) in polys.iter().zip(expected) {
+                assert_eq!(p.evals().Z, (0..size).map(f).collect::<Vec<_>>(), "{n}");
+                check_evaluate(*p, &mut rng);
+            }
+
+            let random: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+            check_evaluate(&EqPolynomial::new(random.clone()), &mut rng);
+            check_evaluate(&EqPlusOnePolynomial::new(random.clone()), &mut rng);
+            check_evaluate(&LtPolynomial::new(random), &mut rng);
+        }
+    }
+}
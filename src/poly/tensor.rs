@@ -0,0 +1,197 @@
+use crate::poly::plain::DensePolynomial;
+use crate::poly::split::SplitTables;
+use crate::poly::structured::StructuredPolynomial;
+use ark_ff::Field;
+use rayon::prelude::*;
+use std::borrow::Cow;
+
+/// scale * (w_1 ⊗ w_2 ⊗ ... ⊗ w_k), stored as its factors. w_1 covers the top variables, so
+/// (w_1 ⊗ w_2)[i * |w_2| + j] = w_1[i] * w_2[j].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TensorPolynomial<F: Field> {
+    scale: F,
+    /// Each over at least one variable.
+    factors: Vec<DensePolynomial<F>>,
+}
+
+impl<F: Field> TensorPolynomial<F> {
+    pub fn new(factors: Vec<Vec<F>>) -> Self {
+        let factors = factors.into_iter().map(DensePolynomial::new).collect();
+        TensorPolynomial::from_factors(F::one(), factors)
+    }
+
+    /// eq(point, x) as the product of (1 - point_i, point_i).
+    pub fn eq(point: &[F]) -> Self {
+        TensorPolynomial::new(point.iter().map(|z| vec![F::one() - z, *z]).collect())
+    }
+
+    /// Folds factors over no variables into the scale.
+    fn from_factors(mut scale: F, factors: Vec<DensePolynomial<F>>) -> Self {
+        let factors = factors
+            .into_iter()
+            .filter(|f| {
+                if f.num_vars() == 0 {
+                    scale *= f.get(0);
+                }
+                f.num_vars() > 0
+            })
+            .collect();
+        TensorPolynomial { scale, factors }
+    }
+
+    pub fn factors(&self) -> &[DensePolynomial<F>] {
+        &self.factors
+    }
+
+    pub fn scale(&self) -> F {
+        self.scale
+    }
+
+    /// The same evaluations followed by zeros, over `num_vars` variables: a new top factor is one
+    /// at index 0 and zero elsewhere.
+    pub fn padded(mut self, num_vars: usize) -> Self {
+        let extra = num_vars
+            .checked_sub(StructuredPolynomial::num_vars(&self))
+            .expect("padding cannot remove variables");
+        if extra > 0 {
+            let top = DensePolynomial::new_padded(vec![F::one()], extra);
+            self.factors.insert(0, top);
+        }
+        self
+    }
+
+    /// Folds the top variable of the first factor.
+    pub fn bind_top(&mut self, r: &F) {
+        assert!(!self.factors.is_empty(), "no variable left to bind");
+        let mut factors = std::mem::take(&mut self.factors);
+        factors[0].bound_poly_var_top(r);
+        *self = TensorPolynomial::from_factors(self.scale, factors);
+    }
+
+    /// The scaled split tables, owned so that a prover can fold them as it binds.
+    pub fn to_split_tables(&self) -> SplitTables<F> {
+        let (high, low, _) = self.split_tables();
+        let mut high = high.into_owned();
+        high.par_iter_mut().for_each(|h| *h *= self.scale);
+        SplitTables::new(high, low.into_owned())
+    }
+
+    /// The unscaled w at x is high[x >> low_vars] * low[x & (2^low_vars - 1)], with the factors
+    /// split where the variables come closest to halves. A table that is a single factor is
+    /// borrowed, so the memory stays at the factors plus O(2^(num_vars / 2)).
+    pub fn split_tables(&self) -> (Cow<'_, [F]>, Cow<'_, [F]>, usize) {
+        let num_vars = StructuredPolynomial::num_vars(self);
+        let mut high_vars = 0usize;
+        let split = self
+            .factors
+            .iter()
+            .position(|f| {
+                let before = (2 * high_vars).abs_diff(num_vars);
+                let after = (2 * (high_vars + f.num_vars())).abs_diff(num_vars);
+                high_vars += f.num_vars();
+                after >= before
+            })
+            .unwrap_or(self.factors.len());
+        let (high, low) = self.factors.split_at(split);
+        let low_vars = low.iter().map(|f| f.num_vars()).sum();
+        (expand(high), expand(low), low_vars)
+    }
+}
+
+/// The Kronecker product of `factors`, borrowing a lone factor that is stored in full.
+fn expand<F: Field>(factors: &[DensePolynomial<F>]) -> Cow<'_, [F]> {
+    if let [factor] = factors {
+        if factor.len() == 1 << factor.num_vars() {
+            return Cow::Borrowed(&factor.Z);
+        }
+    }
+    let mut table = vec![F::one()];
+    for factor in factors {
+        let size = 1 << factor.num_vars();
+        let mut next = vec![F::zero(); table.len() * size];
+        next.par_chunks_mut(size)
+            .zip(table.par_iter())
+            .for_each(|(chunk, t)| {
+                for (out, f) in chunk.iter_mut().zip(&factor.Z) {
+                    *out = *t * f;
+                }
+            });
+        table = next;
+    }
+    Cow::Owned(table)
+}
+
+impl<F: Field> StructuredPolynomial<F> for TensorPolynomial<F> {
+    fn num_vars(&self) -> usize {
+        self.factors.iter().map(|f| f.num_vars()).sum()
+    }
+
+    fn evals(&self) -> DensePolynomial<F> {
+        let (high, low, low_vars) = self.split_tables();
+        let mask = (1 << low_vars) - 1;
+        DensePolynomial::from_fn(self.num_vars(), |x| {
+            self.scale * high[x >> low_vars] * low[x & mask]
+        })
+    }
+
+    /// The product of the factors' extensions at their slices of `point`, in O(sum of the factor
+    /// sizes): O(num_vars) for factors of constant size.
+    fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(
+            point.len(),
+            self.num_vars(),
+            "point has the wrong number of variables"
+        );
+        let mut rest = point;
+        self.factors.iter().fold(self.scale, |acc, f| {
+            let (head, tail) = rest.split_at(f.num_vars());
+            rest = tail;
+            acc * f.evaluate(head)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::eq_evals;
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn tensor_matches_expansion() {
+        let mut rng = test_rng();
+        let mut random = |n: usize| -> Vec<Fr> { (0..n).map(|_| Fr::rand(&mut rng)).collect() };
+        for sizes in [vec![4, 8, 2], vec![2; 7], vec![64], vec![2, 1, 32, 3]] {
+            let factors: Vec<Vec<Fr>> = sizes.iter().map(|n| random(*n)).collect();
+            let tensor = TensorPolynomial::new(factors.clone());
+            let expanded = factors.iter().fold(vec![Fr::from(1)], |acc, f| {
+                let size = f.len().next_power_of_two();
+                (0..acc.len() * size)
+                    .map(|i| acc[i / size] * f.get(i % size).copied().unwrap_or_default())
+                    .collect()
+            });
+            let evals = tensor.evals();
+            assert_eq!(evals.Z, expanded, "{sizes:?}");
+
+            let point = random(tensor.num_vars());
+            assert_eq!(tensor.evaluate(&point), evals.evaluate(&point));
+
+            let mut tables = tensor.to_split_tables();
+            assert_eq!(tables.to_dense(), evals);
+            let (mut bound, mut dense) = (tensor.clone(), evals);
+            for r in &point {
+                bound.bind_top(r);
+                dense.bound_poly_var_top(r);
+                tables.bind_top(r);
+                assert_eq!(bound.evals(), dense);
+                assert_eq!(tables.to_dense(), dense);
+            }
+            assert!(bound.factors().is_empty());
+            assert_eq!(bound.scale(), tensor.evaluate(&point));
+        }
+
+        let point = random(5);
+        assert_eq!(TensorPolynomial::eq(&point).evals().Z, eq_evals(&point));
+    }
+}
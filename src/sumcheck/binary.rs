@@ -0,0 +1,280 @@
+//! The cubic sumcheck of sum(eq * a * b) over GF(2^128) in the binary tower.
+//!
+//! In characteristic 2 the integers 2 and 3 are 0 and 1 again, so round polynomials are evaluated
+//! on GF(4) = {0, 1, ω, ω + 1} instead of {0, 1, 2, 3}. The witnesses a and b are packed elements
+//! of a small tower level, bits by default; the first round works on them directly and its bind
+//! lifts them to GF(2^128).
+
+use crate::field::binary::{Packed, TowerField, B1, B128, OMEGA};
+use crate::sumcheck::SumcheckError;
+use rayon::prelude::*;
+
+/// The evaluation points of the round polynomials: 0, 1, ω and ω + 1, whose bits are 0 to 3.
+pub const DOMAIN: [B128; 4] = [B128::new(0), B128::new(1), B128::new(2), B128::new(3)];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinarySumcheckProof {
+    /// Evaluations on `DOMAIN`.
+    pub round_polys: Vec<(B128, B128, B128, B128)>,
+    pub rs: Vec<B128>,
+}
+
+impl BinarySumcheckProof {
+    /// The transcript of `CubicSumcheckProof`, with 13 and 29 taken as bit patterns since the
+    /// integers reduce to 1 in characteristic 2.
+    fn fiat_shamir(round_poly: (B128, B128, B128, B128)) -> B128 {
+        (round_poly.0 * round_poly.1 * round_poly.2 * round_poly.3 + B128::new(13)) * B128::new(29)
+    }
+
+    /// Evaluates the cubic given by its evaluations on `DOMAIN` at `r`. Each Lagrange denominator
+    /// is the derivative of t^4 + t at a point of GF(4), which is 1, so only the numerators
+    /// remain.
+    pub fn eval_uni(evals: (B128, B128, B128, B128), r: &B128) -> B128 {
+        let [r0, r1, r2, r3] = DOMAIN.map(|x| *r + x);
+        let (low, high) = (r0 * r1, r2 * r3);
+        evals.0 * r1 * high + evals.1 * r0 * high + evals.2 * low * r3 + evals.3 * low * r2
+    }
+
+    /// returns a claim
+    pub fn verify(&self, claim: &B128) -> Result<B128, SumcheckError<B128>> {
+        let num_rounds = self.round_polys.len();
+        if self.rs.len() != num_rounds {
+            return Err(SumcheckError::RoundCountMismatch {
+                round_polys: num_rounds,
+                rs: self.rs.len(),
+            });
+        }
+
+        let mut prev_claim = *claim;
+        for (i, round_poly) in self.round_polys.iter().enumerate() {
+            if round_poly.0 + round_poly.1 != prev_claim {
+                return Err(SumcheckError::RoundSumMismatch {
+                    round: i,
+                    expected: prev_claim,
+                    got: round_poly.0 + round_poly.1,
+                });
+            }
+            let r = Self::fiat_shamir(*round_poly);
+            if r != self.rs[i] {
+                return Err(SumcheckError::ChallengeMismatch { round: i });
+            }
+            prev_claim = Self::eval_uni(*round_poly, &r);
+        }
+
+        Ok(prev_claim)
+    }
+}
+
+pub fn binary_claim<T: TowerField>(eq: &[B128], a: &Packed<T>, b: &Packed<T>) -> B128 {
+    eq.par_iter()
+        .enumerate()
+        .map(|(i, eq)| eq.mul_subfield(a.get(i) * b.get(i)))
+        .sum()
+}
+
+enum Witness<T: TowerField> {
+    Packed(Packed<T>, Packed<T>),
+    Lifted(Vec<B128>, Vec<B128>),
+}
+
+/// Proves sum(eq * a * b) for eq over GF(2^128) and a, b packed at tower level `T`.
+pub struct BinarySumcheck<T: TowerField = B1> {
+    eq: Vec<B128>,
+    witness: Witness<T>,
+}
+
+impl<T: TowerField> BinarySumcheck<T> {
+    pub fn new(eq: Vec<B128>, a: Packed<T>, b: Packed<T>) -> Self {
+        assert!(
+            eq.len().is_power_of_two() && a.len() == eq.len() && b.len() == eq.len(),
+            "eq, a and b must have the same power-of-two length (got {}, {}, {})",
+            eq.len(),
+            a.len(),
+            b.len()
+        );
+        BinarySumcheck {
+            eq,
+            witness: Witness::Packed(a, b),
+        }
+    }
+
+    /// Whether a and b are still packed at level `T`.
+    pub fn is_packed(&self) -> bool {
+        matches!(self.witness, Witness::Packed(..))
+    }
+
+    pub fn eval_cubic_top(&self) -> (B128, B128, B128, B128) {
+        let n = self.eq.len() / 2;
+        let eq = &self.eq;
+        let evals = match &self.witness {
+            Witness::Packed(a, b) => (0..n)
+                .into_par_iter()
+                .fold(
+                    || [B128::ZERO; 4],
+                    |mut evals, i| {
+                        let (a0, a1, b0, b1) = (a.get(i), a.get(n + i), b.get(i), b.get(n + i));
+                        let (eq_0, eq_1) = (eq[i], eq[n + i]);
+                        evals[0] += eq_0.mul_subfield(a0 * b0);
+                        evals[1] += eq_1.mul_subfield(a1 * b1);
+
+                        // At x + ω for x in {0, 1}, a * b = (a_x + ω da)(b_x + ω db) is c0 + ω c1
+                        // with c0 and c1 in T, by ω^2 = ω + 1.
+                        let (da, db) = (a0 + a1, b0 + b1);
+                        let dd = da * db;
+                        let eq_m = eq_0 + eq_1;
+                        let eq_2 = eq_0 + eq_m.mul_subfield(OMEGA);
+                        let eq_3 = eq_2 + eq_m;
+                        for (k, eq_x, a_x, b_x) in [(2, eq_2, a0, b0), (3, eq_3, a1, b1)] {
+                            let c0 = a_x * b_x + dd;
+                            let c1 = a_x * db + da * b_x + dd;
+                            evals[k] +=
+                                eq_x.mul_subfield(c0) + eq_x.mul_subfield(OMEGA).mul_subfield(c1);
+                        }
+                        evals
+                    },
+                )
+                .reduce(|| [B128::ZERO; 4], add_evals),
+            Witness::Lifted(a, b) => (0..n)
+                .into_par_iter()
+                .fold(
+                    || [B128::ZERO; 4],
+                    |mut evals, i| {
+                        let (low, high) = (i, n + i);
+                        evals[0] += eq[low] * a[low] * b[low];
+                        evals[1] += eq[high] * a[high] * b[high];
+
+                        let eq_m = eq[high] + eq[low];
+                        let a_m = a[high] + a[low];
+                        let b_m = b[high] + b[low];
+
+                        let eq_2 = eq[low] + eq_m.mul_subfield(OMEGA);
+                        let a_2 = a[low] + a_m.mul_subfield(OMEGA);
+                        let b_2 = b[low] + b_m.mul_subfield(OMEGA);
+                        evals[2] += eq_2 * a_2 * b_2;
+
+                        evals[3] += (eq_2 + eq_m) * (a_2 + a_m) * (b_2 + b_m);
+                        evals
+                    },
+                )
+                .reduce(|| [B128::ZERO; 4], add_evals),
+        };
+        (evals[0], evals[1], evals[2], evals[3])
+    }
+
+    pub fn bind_top(&mut self, r: &B128) {
+        bind_top_in_place(&mut self.eq, r);
+        match &mut self.witness {
+            Witness::Packed(a, b) => {
+                let lift = |p: &Packed<T>| -> Vec<B128> {
+                    let n = p.len() / 2;
+                    (0..n)
+                        .into_par_iter()
+                        .map(|i| {
+                            let (low, high) = (p.get(i), p.get(n + i));
+                            B128::from_subfield(low) + r.mul_subfield(low + high)
+                        })
+                        .collect()
+                };
+                self.witness = Witness::Lifted(lift(a), lift(b));
+            }
+            Witness::Lifted(a, b) => {
+                bind_top_in_place(a, r);
+                bind_top_in_place(b, r);
+            }
+        }
+    }
+
+    pub fn sumcheck_top(&mut self, num_rounds: usize) -> BinarySumcheckProof {
+        let mut round_polys = Vec::with_capacity(num_rounds);
+        let mut rs = Vec::with_capacity(num_rounds);
+        for _ in 0..num_rounds {
+            let evals = self.eval_cubic_top();
+            round_polys.push(evals);
+            let r = BinarySumcheckProof::fiat_shamir(evals);
+            rs.push(r);
+            self.bind_top(&r);
+        }
+        BinarySumcheckProof { round_polys, rs }
+    }
+}
+
+fn add_evals(a: [B128; 4], b: [B128; 4]) -> [B128; 4] {
+    [0, 1, 2, 3].map(|k| a[k] + b[k])
+}
+
+/// low + r * (high - low)
+fn bind_top_in_place(evals: &mut Vec<B128>, r: &B128) {
+    let n = evals.len() / 2;
+    let (left, right) = evals.split_at_mut(n);
+    left.par_iter_mut()
+        .zip(right.par_iter())
+        .for_each(|(low, high)| *low += *r * (*high + *low));
+    evals.truncate(n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::binary::B8;
+    use ark_std::rand::distributions::{Distribution, Standard};
+    use ark_std::{test_rng, UniformRand};
+
+    fn random<T>(n: usize) -> Vec<T>
+    where
+        Standard: Distribution<T>,
+    {
+        let mut rng = test_rng();
+        (0..n).map(|_| T::rand(&mut rng)).collect()
+    }
+
+    fn prove<T: TowerField>(eq: &[B128], a: &[T], b: &[T]) -> (BinarySumcheckProof, B128) {
+        let (a, b) = (Packed::from_slice(a), Packed::from_slice(b));
+        let claim = binary_claim(eq, &a, &b);
+        let mut prover = BinarySumcheck::new(eq.to_vec(), a, b);
+        assert!(prover.is_packed());
+        let proof = prover.sumcheck_top(eq.len().ilog2() as usize);
+        assert!(!prover.is_packed());
+        (proof, claim)
+    }
+
+    #[test]
+    fn binary_sumcheck() {
+        let log_size = 8;
+        let eq = random::<B128>(1 << log_size);
+        let (a, b) = (random::<B1>(1 << log_size), random::<B1>(1 << log_size));
+        let (proof, claim) = prove(&eq, &a, &b);
+        assert!(proof.verify(&claim).is_ok());
+
+        // The packed first round matches the same witness lifted to GF(2^128) up front.
+        let lift = |v: &[B1]| -> Vec<B128> { v.iter().map(|x| B128::from_subfield(*x)).collect() };
+        assert_eq!(prove(&eq, &lift(&a), &lift(&b)).0, proof);
+
+        let (a, b) = (random::<B8>(1 << log_size), random::<B8>(1 << log_size));
+        let (proof, claim) = prove(&eq, &a, &b);
+        assert!(proof.verify(&claim).is_ok());
+
+        let mut bad = proof.clone();
+        bad.round_polys[3].2 += B128::ONE;
+        assert_eq!(
+            bad.verify(&claim),
+            Err(SumcheckError::ChallengeMismatch { round: 3 })
+        );
+        let mut bad = proof;
+        bad.round_polys[0].0 += B128::ONE;
+        assert!(matches!(
+            bad.verify(&claim),
+            Err(SumcheckError::RoundSumMismatch { round: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn eval_uni_interpolates_cubics() {
+        let c = random::<B128>(5);
+        let cubic = |t: B128| c[0] + t * (c[1] + t * (c[2] + t * c[3]));
+        let evals = DOMAIN.map(cubic);
+        let evals = (evals[0], evals[1], evals[2], evals[3]);
+        for x in DOMAIN.into_iter().chain([c[4]]) {
+            assert_eq!(BinarySumcheckProof::eval_uni(evals, &x), cubic(x));
+        }
+    }
+}
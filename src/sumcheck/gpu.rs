@@ -1,5 +1,5 @@
 use crate::poly::gpu::GPUPoly;
-use crate::sumcheck::CubicSumcheck;
+use crate::sumcheck::{pad_dense, CubicSumcheck};
 use ark_bn254::Fr;
 use icicle_bn254::polynomials::DensePolynomial as IngoPoly;
 use icicle_core::polynomials::UnivariatePolynomial;
@@ -36,7 +36,10 @@ fn sum_poly(poly: &IngoPoly, len: usize) -> Fr {
 }
 
 impl CubicSumcheck for GPUSumcheck {
+    /// eq, a and b may have any lengths; missing tails are written out as zeros up to the next
+    /// power of two of the longest before the upload.
     fn new(eq: Vec<Fr>, a: Vec<Fr>, b: Vec<Fr>) -> Self {
+        let [eq, a, b] = pad_dense([eq, a, b]);
         let eq = GPUPoly::new(eq);
         let a = GPUPoly::new(a);
         let b = GPUPoly::new(b);
@@ -0,0 +1,267 @@
+use crate::poly::grid::{domain_indices, interpolate, lagrange_basis, GridPolynomial};
+use crate::sumcheck::{fiat_shamir, SumcheckError};
+use ark_bn254::Fr;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rayon::prelude::*;
+
+/// The points a sumcheck sums over in each variable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SumDomain {
+    /// {0, 1}.
+    Hypercube,
+    /// {0, ..., d}.
+    Grid(usize),
+}
+
+impl SumDomain {
+    pub fn width(self) -> usize {
+        match self {
+            SumDomain::Hypercube => 2,
+            SumDomain::Grid(d) => d + 1,
+        }
+    }
+}
+
+/// Round polynomials as their evaluations at 0, ..., degree.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct GridSumcheckProof<F: Field = Fr> {
+    pub round_polys: Vec<Vec<F>>,
+    pub rs: Vec<F>,
+}
+
+impl<F: Field> GridSumcheckProof<F> {
+    /// Checks each round polynomial has `degree + 1` evaluations that sum over `domain` to the
+    /// previous claim, and returns the claim on the summand at `rs`.
+    pub fn verify(
+        &self,
+        claim: &F,
+        domain: SumDomain,
+        degree: usize,
+    ) -> Result<F, SumcheckError<F>> {
+        let num_rounds = self.round_polys.len();
+        if self.rs.len() != num_rounds {
+            return Err(SumcheckError::RoundCountMismatch {
+                round_polys: num_rounds,
+                rs: self.rs.len(),
+            });
+        }
+
+        let mut prev_claim = *claim;
+        for (i, round_poly) in self.round_polys.iter().enumerate() {
+            if round_poly.len() != degree + 1 || degree + 1 < domain.width() {
+                return Err(SumcheckError::RoundDegreeMismatch {
+                    round: i,
+                    expected: degree + 1,
+                    got: round_poly.len(),
+                });
+            }
+            let sum: F = round_poly[..domain.width()].iter().sum();
+            if sum != prev_claim {
+                return Err(SumcheckError::RoundSumMismatch {
+                    round: i,
+                    expected: prev_claim,
+                    got: sum,
+                });
+            }
+            let r = fiat_shamir(round_poly);
+            if r != self.rs[i] {
+                return Err(SumcheckError::ChallengeMismatch { round: i });
+            }
+            prev_claim = interpolate(round_poly, &r);
+        }
+        Ok(prev_claim)
+    }
+}
+
+/// Sumcheck of the product of grid polynomials over `domain`^num_vars. A round polynomial has
+/// the sum of the factors' degrees, and is sent at as many points plus one; on the hypercube
+/// with multilinear eq, a and b this is the cubic sumcheck, with the same proof.
+pub struct GridSumcheck<F: Field = Fr> {
+    factors: Vec<GridPolynomial<F>>,
+    domain: SumDomain,
+}
+
+impl<F: Field> GridSumcheck<F> {
+    pub fn new(factors: Vec<GridPolynomial<F>>, domain: SumDomain) -> Self {
+        assert!(!factors.is_empty(), "a sumcheck needs at least one factor");
+        let num_vars = factors[0].num_vars();
+        for f in &factors {
+            assert_eq!(f.num_vars(), num_vars, "factors differ in variables");
+            assert!(
+                domain.width() <= f.degree() + 1,
+                "{domain:?} is outside the grid of a degree {} factor",
+                f.degree()
+            );
+        }
+        GridSumcheck { factors, domain }
+    }
+
+    /// The degree of every round polynomial.
+    pub fn degree(&self) -> usize {
+        self.factors.iter().map(|f| f.degree()).sum()
+    }
+
+    /// The sum being proved.
+    pub fn claim(&self) -> F {
+        let num_vars = self.factors[0].num_vars();
+        self.sum_products(num_vars, 1, |values, sum| {
+            sum[0] += values.iter().map(|v| v[0]).product::<F>();
+        })[0]
+    }
+
+    /// s(k) for k in 0..=degree: the sum over the domain of the other variables, with the top
+    /// variable of each factor interpolated to k.
+    pub fn eval_round(&self) -> Vec<F> {
+        let degree = self.degree();
+        let num_vars = self.factors[0].num_vars();
+        let bases: Vec<Vec<Vec<F>>> = self
+            .factors
+            .iter()
+            .map(|f| {
+                (0..=degree)
+                    .map(|k| lagrange_basis(f.degree(), &F::from(k as u64)))
+                    .collect()
+            })
+            .collect();
+        self.sum_products(num_vars - 1, degree + 1, |values, s| {
+            for (k, s_k) in s.iter_mut().enumerate() {
+                *s_k += values
+                    .iter()
+                    .zip(&bases)
+                    .map(|(column, basis)| {
+                        basis[k].iter().zip(column).map(|(l, v)| *l * v).sum::<F>()
+                    })
+                    .product::<F>();
+            }
+        })
+    }
+
+    /// The sum of `f` over the domain of the bottom `vars` variables, where `f` gets each
+    /// factor's evaluations along the variables above at that point and adds its `len` values to
+    /// the sums. The evaluations are read into buffers kept per task.
+    fn sum_products(
+        &self,
+        vars: usize,
+        len: usize,
+        f: impl Fn(&[Vec<F>], &mut [F]) + Sync,
+    ) -> Vec<F> {
+        let width = self.domain.width();
+        let stride = |p: &GridPolynomial<F>| (p.degree() + 1).pow(vars as u32);
+        let indices: Vec<Vec<usize>> = self
+            .factors
+            .iter()
+            .map(|p| domain_indices(vars, width, p.degree() + 1))
+            .collect();
+        (0..width.pow(vars as u32))
+            .into_par_iter()
+            .fold(
+                || {
+                    let values: Vec<Vec<F>> = self
+                        .factors
+                        .iter()
+                        .map(|p| vec![F::zero(); p.evals().len() / stride(p)])
+                        .collect();
+                    (vec![F::zero(); len], values)
+                },
+                |(mut sums, mut values), t| {
+                    for ((p, idx), column) in self.factors.iter().zip(&indices).zip(&mut values) {
+                        let along = p.evals()[idx[t]..].iter().step_by(stride(p));
+                        for (v, e) in column.iter_mut().zip(along) {
+                            *v = *e;
+                        }
+                    }
+                    f(&values, &mut sums);
+                    (sums, values)
+                },
+            )
+            .map(|(sums, _)| sums)
+            .reduce_with(|a, b| a.iter().zip(&b).map(|(a, b)| *a + b).collect())
+            .unwrap_or_else(|| vec![F::zero(); len])
+    }
+
+    pub fn bind_top(&mut self, r: &F) {
+        for f in &mut self.factors {
+            f.bind_top(r);
+        }
+    }
+
+    pub fn sumcheck(&mut self) -> GridSumcheckProof<F> {
+        let num_rounds = self.factors[0].num_vars();
+        let mut round_polys = Vec::with_capacity(num_rounds);
+        let mut rs = Vec::with_capacity(num_rounds);
+        for _ in 0..num_rounds {
+            let round_poly = self.eval_round();
+            let r = fiat_shamir(&round_poly);
+            self.bind_top(&r);
+            round_polys.push(round_poly);
+            rs.push(r);
+        }
+        GridSumcheckProof { round_polys, rs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::{eq_evals, rand_vec, DensePolynomial};
+    use crate::sumcheck::plain::PlainSumcheck;
+    use crate::sumcheck::{cubic_claim, CubicSumcheck};
+
+    #[test]
+    fn grid_sumcheck_matches_cubic_and_verifies() {
+        let num_vars = 6;
+        let point = rand_vec::<Fr>(num_vars);
+        let (a, b) = (rand_vec::<Fr>(1 << num_vars), rand_vec::<Fr>(1 << num_vars));
+        let multilinear = [eq_evals(&point), a.clone(), b.clone()]
+            .map(|z| GridPolynomial::from_dense(&DensePolynomial::new(z)));
+
+        // Multilinear eq, a and b over the hypercube: the cubic sumcheck.
+        let mut prover = GridSumcheck::new(multilinear.to_vec(), SumDomain::Hypercube);
+        let claim = cubic_claim(&eq_evals(&point), &a, &b);
+        assert_eq!(prover.claim(), claim);
+        let proof = prover.sumcheck();
+        let cubic = PlainSumcheck::new(eq_evals(&point), a, b).sumcheck_top(num_vars);
+        let cubic_polys: Vec<Vec<Fr>> = cubic
+            .round_polys
+            .iter()
+            .map(|(s0, s1, s2, s3)| vec![*s0, *s1, *s2, *s3])
+            .collect();
+        assert_eq!((&proof.round_polys, &proof.rs), (&cubic_polys, &cubic.rs));
+        assert_eq!(
+            proof.verify(&claim, SumDomain::Hypercube, 3),
+            cubic.verify(&claim)
+        );
+
+        // A multiquadratic q times a multilinear c, over the hypercube and over {0, 1, 2}^n.
+        let [eq, a, b] = multilinear;
+        let q = GridPolynomial::product(&[&a, &b], 2);
+        for domain in [SumDomain::Hypercube, SumDomain::Grid(1), SumDomain::Grid(2)] {
+            let factors = match domain {
+                SumDomain::Grid(2) => vec![q.clone(), q.clone()],
+                _ => vec![q.clone(), eq.clone()],
+            };
+            let mut prover = GridSumcheck::new(factors.clone(), domain);
+            let (claim, degree) = (prover.claim(), prover.degree());
+            let proof = prover.sumcheck();
+            assert!(proof.round_polys.iter().all(|s| s.len() == degree + 1));
+            let expected: Fr = factors.iter().map(|f| f.evaluate(&proof.rs)).product();
+            assert_eq!(
+                proof.verify(&claim, domain, degree),
+                Ok(expected),
+                "{domain:?}"
+            );
+
+            assert!(matches!(
+                proof.verify(&claim, domain, degree + 1),
+                Err(SumcheckError::RoundDegreeMismatch { round: 0, .. })
+            ));
+            assert!(matches!(
+                proof.verify(&(claim + Fr::from(1)), domain, degree),
+                Err(SumcheckError::RoundSumMismatch { round: 0, .. })
+            ));
+        }
+        let grid_sum = GridSumcheck::new(vec![q.clone()], SumDomain::Grid(2)).claim();
+        assert_eq!(grid_sum, q.sum_over(3));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::sumcheck::{pad_dense, CubicSumcheck, ProverOptions};
+use ark_bn254::Fr;
+use ark_ff::Field;
+use rayon::prelude::*;
+
+/// eq, a and b stored as `[eq[i], a[i], b[i]]` triples, so a round reads the low and high halves
+/// as two streams instead of six.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterleavedPolys<F: Field> {
+    pub Z: Vec<[F; 3]>,
+}
+
+impl<F: Field> InterleavedPolys<F> {
+    pub fn from_split(eq: &[F], a: &[F], b: &[F]) -> Self {
+        assert!(
+            eq.len().is_power_of_two() && a.len() == eq.len() && b.len() == eq.len(),
+            "eq, a and b must have the same power-of-two length (got {}, {}, {})",
+            eq.len(),
+            a.len(),
+            b.len()
+        );
+        let mut z = Vec::with_capacity(eq.len());
+        eq.par_iter()
+            .zip(a.par_iter())
+            .zip(b.par_iter())
+            .map(|((eq, a), b)| [*eq, *a, *b])
+            .collect_into_vec(&mut z);
+        InterleavedPolys { Z: z }
+    }
+
+    /// Back to separate (eq, a, b) vectors.
+    pub fn to_split(&self) -> (Vec<F>, Vec<F>, Vec<F>) {
+        let column = |k: usize| self.Z.par_iter().map(|t| t[k]).collect();
+        (column(0), column(1), column(2))
+    }
+
+    /// Binds the top variable of all three polys in one pass: low + r * (high - low).
+    pub fn bound_poly_var_top_min_len(&mut self, r: &F, min_len: usize) {
+        let n = self.Z.len() / 2;
+        let (left, right) = self.Z.split_at_mut(n);
+
+        left.par_iter_mut()
+            .zip(right.par_iter())
+            .with_min_len(min_len)
+            .for_each(|(low, high)| {
+                for k in 0..3 {
+                    low[k] += *r * (high[k] - low[k]);
+                }
+            });
+
+        self.Z.truncate(n);
+    }
+
+    pub fn bound_poly_var_top_par(&mut self, r: &F) {
+        self.bound_poly_var_top_min_len(r, 1);
+    }
+}
+
+/// The plain prover over `InterleavedPolys`: the same arithmetic, one pass per kernel.
+pub struct InterleavedSumcheck<F: Field = Fr> {
+    polys: InterleavedPolys<F>,
+    options: ProverOptions,
+}
+
+impl<F: Field> InterleavedSumcheck<F> {
+    pub fn from_polys(polys: InterleavedPolys<F>) -> Self {
+        Self {
+            polys,
+            options: ProverOptions::default(),
+        }
+    }
+}
+
+impl<F: Field> CubicSumcheck<F> for InterleavedSumcheck<F> {
+    /// eq, a and b may have any lengths; missing tails are written out as zeros up to the next
+    /// power of two of the longest.
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        let [eq, a, b] = pad_dense([eq, a, b]);
+        Self::from_polys(InterleavedPolys::from_split(&eq, &a, &b))
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    fn eval_cubic_top(&mut self) -> (F, F, F, F) {
+        let n = self.polys.Z.len() / 2;
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        let (low, high) = self.polys.Z.split_at(n);
+
+        // low + r * (high - low)
+        let (eval_0, eval_1, eval_2, eval_3) = low
+            .par_iter()
+            .zip(high.par_iter())
+            .with_min_len(min_len)
+            .map(|([eq_low, a_low, b_low], [eq_high, a_high, b_high])| {
+                let eval_0: F = *eq_low * a_low * b_low;
+                let eval_1: F = *eq_high * a_high * b_high;
+
+                let eq_m: F = *eq_high - eq_low;
+                let a_m: F = *a_high - a_low;
+                let b_m: F = *b_high - b_low;
+
+                let eq_2 = *eq_high + eq_m;
+                let a_2 = *a_high + a_m;
+                let b_2 = *b_high + b_m;
+                let eval_2 = eq_2 * a_2 * b_2;
+
+                let eq_3 = eq_2 + eq_m;
+                let a_3 = a_2 + a_m;
+                let b_3 = b_2 + b_m;
+                let eval_3 = eq_3 * a_3 * b_3;
+
+                (eval_0, eval_1, eval_2, eval_3)
+            })
+            .reduce(
+                || (F::zero(), F::zero(), F::zero(), F::zero()),
+                |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3),
+            );
+
+        (eval_0, eval_1, eval_2, eval_3)
+    }
+
+    fn bind_top(&mut self, r: &F) {
+        let n = self.polys.Z.len() / 2;
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        self.polys.bound_poly_var_top_min_len(r, min_len);
+
+        self.options.shrink.apply(&mut self.polys.Z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::{rand_vec, DensePolynomial};
+
+    #[test]
+    fn binds_like_the_split_layout() {
+        let evals = rand_vec::<Fr>(64);
+        let (eq, a, b) = (
+            evals[..16].to_vec(),
+            evals[16..32].to_vec(),
+            evals[32..48].to_vec(),
+        );
+        let mut polys = InterleavedPolys::from_split(&eq, &a, &b);
+        assert_eq!(polys.to_split(), (eq.clone(), a.clone(), b.clone()));
+
+        let mut split = [eq, a, b].map(DensePolynomial::new);
+        for r in &evals[48..50] {
+            polys.bound_poly_var_top_par(r);
+            split.iter_mut().for_each(|p| p.bound_poly_var_top(r));
+        }
+        let [eq, a, b] = split.map(|p| p.Z);
+        assert_eq!(polys.to_split(), (eq, a, b));
+    }
+}
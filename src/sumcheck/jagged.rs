@@ -0,0 +1,105 @@
+use crate::field::lazy::LazySum;
+use crate::poly::jagged::{JaggedEqPolynomial, JaggedPolynomial};
+use crate::poly::structured::StructuredPolynomial;
+use crate::sumcheck::plain::PlainSumcheck;
+use crate::sumcheck::{CubicSumcheck, CubicSumcheckProof, ProverOptions};
+use ark_bn254::Fr;
+
+/// Proves sum(eq * a * b) over the dense form of jagged a and b as the same sum over their packed
+/// values, with eq replaced by `JaggedEqPolynomial`. The rounds bind the packed variables, so the
+/// work follows the number of values rather than the padded columns. The verifier evaluates eq
+/// itself through `Factor::Public` and takes a and b as claims on the packed polynomials.
+pub struct JaggedSumcheck<F: LazySum = Fr> {
+    sumcheck: PlainSumcheck<F>,
+    num_rounds: usize,
+}
+
+impl<F: LazySum> JaggedSumcheck<F> {
+    pub fn new(
+        eq: &JaggedEqPolynomial<F>,
+        a: &JaggedPolynomial<F>,
+        b: &JaggedPolynomial<F>,
+    ) -> Self {
+        assert!(
+            a.shape() == eq.shape() && b.shape() == eq.shape(),
+            "eq, a and b must have the same jagged shape"
+        );
+        let sumcheck = PlainSumcheck::new(eq.evals().Z, a.packed().Z.clone(), b.packed().Z.clone());
+        JaggedSumcheck {
+            sumcheck,
+            num_rounds: eq.num_vars(),
+        }
+    }
+
+    pub fn with_options(self, options: ProverOptions) -> Self {
+        JaggedSumcheck {
+            sumcheck: self.sumcheck.with_options(options),
+            ..self
+        }
+    }
+
+    /// One round per packed variable; `rs` is the point of the packed polynomials.
+    pub fn sumcheck(&mut self) -> CubicSumcheckProof<F> {
+        self.sumcheck.sumcheck_top(self.num_rounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::jagged::JaggedShape;
+    use crate::sumcheck::{cubic_claim, Factor, SumcheckError};
+    use ark_std::{test_rng, UniformRand};
+
+    #[test]
+    fn jagged_sumcheck_matches_padded_dense() {
+        let mut rng = test_rng();
+        let heights = [13, 0, 32, 5, 1, 20, 7];
+        let row_vars = 5;
+        let shape = JaggedShape::new(&heights, row_vars);
+        let mut random_jagged = || {
+            let values = (0..shape.packed_len())
+                .map(|_| Fr::rand(&mut rng))
+                .collect();
+            JaggedPolynomial::from_packed(shape.clone(), values)
+        };
+        let (a, b) = (random_jagged(), random_jagged());
+        let point: Vec<Fr> = (0..shape.num_vars()).map(|_| Fr::rand(&mut rng)).collect();
+        let eq = JaggedEqPolynomial::new(shape.clone(), point.clone());
+
+        let proof = JaggedSumcheck::new(&eq, &a, &b).sumcheck();
+        assert_eq!(proof.rs.len(), shape.packed_vars());
+
+        // The claim is that of the padded dense form, which PlainSumcheck proves as usual.
+        let pad = |p: &JaggedPolynomial<Fr>| {
+            let mut z = p.to_dense().Z;
+            z.resize(1 << shape.num_vars(), Fr::from(0));
+            z
+        };
+        let dense_eq = crate::poly::plain::eq_evals(&point);
+        let claim = cubic_claim(&dense_eq, &pad(&a), &pad(&b));
+        assert_eq!(
+            claim,
+            cubic_claim(&eq.evals().Z, &a.packed().Z, &b.packed().Z)
+        );
+        let dense = PlainSumcheck::new(dense_eq, pad(&a), pad(&b)).sumcheck_top(shape.num_vars());
+        dense.verify(&claim).unwrap();
+
+        let (a_eval, b_eval) = (
+            a.packed().evaluate(&proof.rs),
+            b.packed().evaluate(&proof.rs),
+        );
+        let factors = |a_eval| {
+            [
+                Factor::Public(&eq),
+                Factor::Claimed(a_eval),
+                Factor::Claimed(b_eval),
+            ]
+        };
+        assert_eq!(proof.verify_factors(&claim, factors(a_eval)), Ok(()));
+        assert!(matches!(
+            proof.verify_factors(&claim, factors(b_eval)),
+            Err(SumcheckError::FinalClaimMismatch { .. })
+        ));
+    }
+}
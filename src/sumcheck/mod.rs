@@ -1,20 +1,87 @@
+use crate::field::counted::{count_ops, Counted, OpCounts};
+use crate::memory;
+use crate::poly::plain::Shrink;
+use crate::poly::structured::StructuredPolynomial;
 use ark_bn254::Fr;
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use rayon::prelude::*;
+use std::time::{Duration, Instant};
 
+pub mod binary;
 #[cfg(feature = "gpu")]
 pub mod gpu;
+pub mod grid;
+pub mod interleaved;
+pub mod jagged;
 pub mod plain;
+pub mod scratch;
 pub mod simd;
+pub mod small_field;
+pub mod sparse;
+pub mod tensor;
+pub mod tuning;
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CubicSumcheckProof<F: Field = Fr> {
+    pub round_polys: Vec<(F, F, F, F)>,
+    pub rs: Vec<F>,
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct CubicSumcheckProof {
-    round_polys: Vec<(Fr, Fr, Fr, Fr)>,
-    rs: Vec<Fr>,
+pub enum SumcheckError<F = Fr> {
+    /// The proof carries a different number of challenges than round polynomials.
+    RoundCountMismatch { round_polys: usize, rs: usize },
+    /// s_i(0) + s_i(1) does not equal the claim carried over from the previous round.
+    RoundSumMismatch { round: usize, expected: F, got: F },
+    /// The challenge in the proof is not the Fiat-Shamir challenge of the round polynomial.
+    ChallengeMismatch { round: usize },
+    /// The product of eq, a and b at the challenges does not equal the last round's claim.
+    FinalClaimMismatch { expected: F, got: F },
+    /// A round polynomial has a different number of evaluations than its degree bound allows.
+    RoundDegreeMismatch {
+        round: usize,
+        expected: usize,
+        got: usize,
+    },
 }
 
-impl CubicSumcheckProof {
-    fn fiat_shamir(round_poly: (Fr, Fr, Fr, Fr)) -> Fr {
-        (round_poly.0 * round_poly.1 * round_poly.2 * round_poly.3 + Fr::from(13)) * Fr::from(29)
+impl<F: std::fmt::Display> std::fmt::Display for SumcheckError<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SumcheckError::RoundCountMismatch { round_polys, rs } => write!(
+                f,
+                "proof has {round_polys} round polynomials but {rs} challenges"
+            ),
+            SumcheckError::RoundSumMismatch {
+                round,
+                expected,
+                got,
+            } => write!(
+                f,
+                "round {round}: s(0) + s(1) = {got}, expected claim {expected}"
+            ),
+            SumcheckError::ChallengeMismatch { round } => {
+                write!(f, "round {round}: challenge does not match transcript")
+            }
+            SumcheckError::FinalClaimMismatch { expected, got } => write!(
+                f,
+                "eq * a * b at the challenges is {got}, expected final claim {expected}"
+            ),
+            SumcheckError::RoundDegreeMismatch {
+                round,
+                expected,
+                got,
+            } => write!(f, "round {round}: {got} evaluations, expected {expected}"),
+        }
+    }
+}
+
+impl<F: std::fmt::Debug + std::fmt::Display> std::error::Error for SumcheckError<F> {}
+
+impl<F: Field> CubicSumcheckProof<F> {
+    fn fiat_shamir(round_poly: (F, F, F, F)) -> F {
+        fiat_shamir(&[round_poly.0, round_poly.1, round_poly.2, round_poly.3])
     }
 
     /// Evaluates the univariate polynomial as specified by its evaluations over [0, ... 3]
@@ -22,135 +89,417 @@ impl CubicSumcheckProof {
     ///
     /// evals: f(0), f(1), f(2), f(3)
     /// r: f(r)
-    fn eval_uni(evals: (Fr, Fr, Fr, Fr), r: &Fr) -> Fr {
+    pub fn eval_uni(evals: (F, F, F, F), r: &F) -> F {
         let (f0, f1, f2, f3) = evals;
 
-        let l0 = (*r - Fr::from(1)) * (*r - Fr::from(2)) * (*r - Fr::from(3))
-            / ((Fr::from(0) - Fr::from(1))
-                * (Fr::from(0) - Fr::from(2))
-                * (Fr::from(0) - Fr::from(3)));
-        let l1 = (*r - Fr::from(0)) * (*r - Fr::from(2)) * (*r - Fr::from(3))
-            / ((Fr::from(1) - Fr::from(0))
-                * (Fr::from(1) - Fr::from(2))
-                * (Fr::from(1) - Fr::from(3)));
-        let l2 = (*r - Fr::from(0)) * (*r - Fr::from(1)) * (*r - Fr::from(3))
-            / ((Fr::from(2) - Fr::from(0))
-                * (Fr::from(2) - Fr::from(1))
-                * (Fr::from(2) - Fr::from(3)));
-        let l3 = (*r - Fr::from(0)) * (*r - Fr::from(1)) * (*r - Fr::from(2))
-            / ((Fr::from(3) - Fr::from(0))
-                * (Fr::from(3) - Fr::from(1))
-                * (Fr::from(3) - Fr::from(2)));
+        let l0 = (*r - F::from(1u64)) * (*r - F::from(2u64)) * (*r - F::from(3u64))
+            / ((F::from(0u64) - F::from(1u64))
+                * (F::from(0u64) - F::from(2u64))
+                * (F::from(0u64) - F::from(3u64)));
+        let l1 = (*r - F::from(0u64)) * (*r - F::from(2u64)) * (*r - F::from(3u64))
+            / ((F::from(1u64) - F::from(0u64))
+                * (F::from(1u64) - F::from(2u64))
+                * (F::from(1u64) - F::from(3u64)));
+        let l2 = (*r - F::from(0u64)) * (*r - F::from(1u64)) * (*r - F::from(3u64))
+            / ((F::from(2u64) - F::from(0u64))
+                * (F::from(2u64) - F::from(1u64))
+                * (F::from(2u64) - F::from(3u64)));
+        let l3 = (*r - F::from(0u64)) * (*r - F::from(1u64)) * (*r - F::from(2u64))
+            / ((F::from(3u64) - F::from(0u64))
+                * (F::from(3u64) - F::from(1u64))
+                * (F::from(3u64) - F::from(2u64)));
 
         f0 * l0 + f1 * l1 + f2 * l2 + f3 * l3
     }
 
     /// returns a claim
-    fn verify(&self, claim: &Fr) -> Fr {
+    pub fn verify(&self, claim: &F) -> Result<F, SumcheckError<F>> {
         let num_rounds = self.round_polys.len();
-        assert_eq!(self.rs.len(), num_rounds);
-
-        let mut prev_claim: Fr = claim.to_owned();
+        if self.rs.len() != num_rounds {
+            return Err(SumcheckError::RoundCountMismatch {
+                round_polys: num_rounds,
+                rs: self.rs.len(),
+            });
+        }
 
-        let mut v_rs = Vec::with_capacity(num_rounds);
+        let mut prev_claim: F = claim.to_owned();
 
         for i in 0..num_rounds {
             let round_poly = self.round_polys[i];
-            assert_eq!(round_poly.0 + round_poly.1, prev_claim, "Round {i}");
+            if round_poly.0 + round_poly.1 != prev_claim {
+                return Err(SumcheckError::RoundSumMismatch {
+                    round: i,
+                    expected: prev_claim,
+                    got: round_poly.0 + round_poly.1,
+                });
+            }
             let r = Self::fiat_shamir(round_poly);
-            v_rs.push(r);
+            if r != self.rs[i] {
+                return Err(SumcheckError::ChallengeMismatch { round: i });
+            }
             prev_claim = Self::eval_uni(round_poly, &r);
         }
 
-        assert_eq!(v_rs, self.rs);
+        Ok(prev_claim)
+    }
 
-        prev_claim
+    /// `verify`, then checks the final claim against eq * a * b at `rs`, evaluating the public
+    /// factors itself and taking the others' evaluations as claimed.
+    pub fn verify_factors(
+        &self,
+        claim: &F,
+        factors: [Factor<F>; 3],
+    ) -> Result<(), SumcheckError<F>> {
+        let expected = self.verify(claim)?;
+        let got = factors
+            .iter()
+            .map(|factor| match factor {
+                Factor::Public(poly) => poly.evaluate(&self.rs),
+                Factor::Claimed(eval) => *eval,
+            })
+            .product();
+        if got != expected {
+            return Err(SumcheckError::FinalClaimMismatch { expected, got });
+        }
+        Ok(())
     }
 }
 
-trait CubicSumcheck {
-    fn new(eq: Vec<Fr>, a: Vec<Fr>, b: Vec<Fr>) -> Self;
-    fn eval_cubic_top(&mut self) -> (Fr, Fr, Fr, Fr);
-    fn bind_top(&mut self, r: &Fr);
+/// The challenge of a round polynomial given by its evaluations.
+pub(crate) fn fiat_shamir<F: Field>(round_poly: &[F]) -> F {
+    let product = round_poly[1..].iter().fold(round_poly[0], |acc, e| acc * e);
+    let r = (product + F::from(13u64)) * F::from(29u64);
+    let mut upper = r.to_base_prime_field_elements().skip(1);
+    if F::extension_degree() == 1 || upper.any(|c| !c.is_zero()) {
+        return r;
+    }
+    // Round polynomials of a base-field start have base-field values, and so would r. Each
+    // coordinate is instead hashed from all of the round polynomial's, with a multiplier of its
+    // own, so the challenge covers the whole extension.
+    let inputs: Vec<F::BasePrimeField> = round_poly
+        .iter()
+        .flat_map(|e| e.to_base_prime_field_elements())
+        .collect();
+    let coords: Vec<F::BasePrimeField> = (1..=F::extension_degree())
+        .map(|k| {
+            let m = F::BasePrimeField::from(29 + 2 * k);
+            inputs
+                .iter()
+                .fold(F::BasePrimeField::from(13 * k), |acc, x| (acc + x) * m)
+        })
+        .collect();
+    F::from_base_prime_field_elems(&coords).unwrap()
+}
 
-    #[tracing::instrument(skip_all)]
-    fn sumcheck_top(&mut self, num_rounds: usize) -> CubicSumcheckProof {
-        let mut round_polys = Vec::with_capacity(num_rounds);
-        let mut rs = Vec::with_capacity(num_rounds);
+/// Variables of the zero-padded eq, a and b: those of the next power of two of the longest.
+pub(crate) fn num_vars<F>(polys: [&[F]; 3]) -> usize {
+    let len = polys.iter().map(|p| p.len()).max().unwrap();
+    len.next_power_of_two().ilog2() as usize
+}
+
+/// eq, a and b with their missing tails written out as zeros, for the provers that keep them
+/// dense.
+pub(crate) fn pad_dense<F: Field>(mut polys: [Vec<F>; 3]) -> [Vec<F>; 3] {
+    let len = 1 << num_vars([&polys[0], &polys[1], &polys[2]]);
+    for poly in &mut polys {
+        poly.resize(len, F::zero());
+    }
+    polys
+}
 
-        for round in 0..num_rounds {
-            let start_time = std::time::Instant::now();
+/// One of eq, a and b at the end of a sumcheck.
+pub enum Factor<'a, F: Field> {
+    /// A polynomial the verifier evaluates itself.
+    Public(&'a dyn StructuredPolynomial<F>),
+    /// An evaluation to be checked elsewhere, e.g. by an opening proof.
+    Claimed(F),
+}
 
-            let evals = self.eval_cubic_top();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Plain,
+    Simd,
+    /// The plain prover over eq, a and b stored as interleaved triples.
+    Interleaved,
+    #[cfg(feature = "gpu")]
+    Gpu,
+}
 
-            round_polys.push(evals);
-            let r = CubicSumcheckProof::fiat_shamir(evals);
-            rs.push(r);
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Backend::Plain),
+            "simd" => Ok(Backend::Simd),
+            "interleaved" => Ok(Backend::Interleaved),
+            #[cfg(feature = "gpu")]
+            "gpu" => Ok(Backend::Gpu),
+            #[cfg(not(feature = "gpu"))]
+            "gpu" => Err("backend 'gpu' requires building with --features gpu".to_string()),
+            _ => Err(format!("unknown backend '{s}' (expected plain, simd, interleaved or gpu)")),
+        }
+    }
+}
 
-            self.bind_top(&r);
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
-            let duration = start_time.elapsed();
-            println!("Round {}: {:?}", round, duration);
+impl Backend {
+    pub const ALL: &'static [Backend] = &[
+        Backend::Plain,
+        Backend::Simd,
+        Backend::Interleaved,
+        #[cfg(feature = "gpu")]
+        Backend::Gpu,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Plain => "plain",
+            Backend::Simd => "simd",
+            Backend::Interleaved => "interleaved",
+            #[cfg(feature = "gpu")]
+            Backend::Gpu => "gpu",
         }
+    }
 
-        CubicSumcheckProof { round_polys, rs }
+    /// Runs the full top-down sumcheck for sum(eq * a * b) on this backend.
+    pub fn prove(self, eq: Vec<Fr>, a: Vec<Fr>, b: Vec<Fr>) -> CubicSumcheckProof {
+        self.prove_timed(eq, a, b, ProverOptions::default()).0
+    }
+
+    pub fn prove_timed(
+        self,
+        eq: Vec<Fr>,
+        a: Vec<Fr>,
+        b: Vec<Fr>,
+        options: ProverOptions,
+    ) -> (CubicSumcheckProof, Vec<RoundTiming>) {
+        let num_rounds = num_vars([&eq, &a, &b]);
+        match self {
+            Backend::Plain => plain::PlainSumcheck::new(eq, a, b)
+                .with_options(options)
+                .sumcheck_top_timed(num_rounds),
+            Backend::Simd => simd::SIMDSumcheck::new(eq, a, b)
+                .with_options(options)
+                .sumcheck_top_timed(num_rounds),
+            Backend::Interleaved => interleaved::InterleavedSumcheck::new(eq, a, b)
+                .with_options(options)
+                .sumcheck_top_timed(num_rounds),
+            #[cfg(feature = "gpu")]
+            Backend::Gpu => gpu::GPUSumcheck::new(eq, a, b)
+                .with_options(options)
+                .sumcheck_top_timed(num_rounds),
+        }
     }
 }
 
-pub mod bench {
-    use bench::simd::SIMDSumcheck;
+pub fn cubic_claim<F: Field>(eq: &[F], a: &[F], b: &[F]) -> F {
+    eq.par_iter()
+        .zip(a.par_iter())
+        .zip(b.par_iter())
+        .map(|((eq, a), b)| *eq * a * b)
+        .sum()
+}
 
-    use super::*;
-    #[cfg(feature = "gpu")]
-    use crate::sumcheck::gpu::GPUSumcheck;
-    use crate::sumcheck::plain::PlainSumcheck;
-    use std::time::Instant;
+/// Knobs shared by the CPU provers; backends ignore the ones that do not apply to them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProverOptions {
+    pub shrink: Shrink,
+    pub strategy: RoundStrategy,
+}
 
-    pub fn main() {
-        let log_size = 28;
-        let size = 1 << log_size;
-        let mut evals = Vec::with_capacity(size);
+/// How one round's work is spread over the rayon pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Execution {
+    /// A single task on the calling thread.
+    Sequential,
+    /// One task per thread, and no nested joins across polynomials.
+    Reduced,
+    /// The backend's full chunking, with work-stealing across polynomials.
+    Parallel,
+}
 
-        for i in 0..size {
-            evals.push(Fr::from(i as u64));
+impl Execution {
+    /// Minimum elements per rayon task over a half-length of `n`.
+    pub fn min_len(self, n: usize) -> usize {
+        match self {
+            Execution::Sequential => n.max(1),
+            Execution::Reduced => n.div_ceil(rayon::current_num_threads()).max(1),
+            Execution::Parallel => 1,
         }
+    }
+}
 
-        let claim: Fr = evals.par_iter().map(|eval| eval * eval * eval).sum();
+/// Picks the execution of a round from its half-length. Late rounds of a large sumcheck are a few
+/// thousand elements, where task spawning and joins cost more than the arithmetic. The SIMD
+/// prover follows it only at sizes its tuned profile does not cover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundStrategy {
+    /// Half-lengths below this run sequentially.
+    pub sequential_below: usize,
+    /// Half-lengths from this up run fully parallel; those in between use `Execution::Reduced`.
+    pub parallel_from: usize,
+}
 
-        let mut plain = PlainSumcheck::new(evals.clone(), evals.clone(), evals.clone());
-        let start_plain = Instant::now();
-        let plain_proof = plain.sumcheck_top(log_size);
-        let duration_plain = start_plain.elapsed();
-        println!("PlainSumcheck: {:?}\n\n", duration_plain);
+impl Default for RoundStrategy {
+    fn default() -> Self {
+        RoundStrategy {
+            sequential_below: 1 << 10,
+            parallel_from: 1 << 16,
+        }
+    }
+}
 
-        // SIMD
-        let mut simd = SIMDSumcheck::new(evals.clone(), evals.clone(), evals.clone());
-        let start_simd = Instant::now();
-        tracing_texray::examine(tracing::info_span!("simd_sumcheck")).in_scope(|| {
-            let simd_proof = simd.sumcheck_top(log_size);
+impl RoundStrategy {
+    /// Every round fully parallel, as the provers ran before the strategy existed.
+    pub const PARALLEL: RoundStrategy = RoundStrategy {
+        sequential_below: 0,
+        parallel_from: 0,
+    };
+
+    pub fn new(sequential_below: usize, parallel_from: usize) -> Result<Self, String> {
+        if parallel_from < sequential_below {
+            return Err(format!(
+                "parallel threshold {parallel_from} is below the sequential threshold {sequential_below}"
+            ));
+        }
+        Ok(RoundStrategy {
+            sequential_below,
+            parallel_from,
+        })
+    }
 
-            assert_eq!(plain_proof, simd_proof);
-        });
-        let duration_simd = start_simd.elapsed();
-        println!("SIMDSumcheck: {:?}", duration_simd);
+    pub fn execution(&self, n: usize) -> Execution {
+        if n < self.sequential_below {
+            Execution::Sequential
+        } else if n < self.parallel_from {
+            Execution::Reduced
+        } else {
+            Execution::Parallel
+        }
+    }
+}
 
-        #[cfg(feature = "gpu")]
-        {
-            let mut gpu = GPUSumcheck::new(evals.clone(), evals.clone(), evals.clone());
-            let start_gpu = Instant::now();
-            tracing_texray::examine(tracing::info_span!("gpu_sumcheck")).in_scope(|| {
-                let gpu_proof = gpu.sumcheck_top(log_size);
+pub trait CubicSumcheck<F: Field = Fr>: Sized {
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self;
+
+    fn with_options(self, _options: ProverOptions) -> Self {
+        self
+    }
+
+    fn eval_cubic_top(&mut self) -> (F, F, F, F);
+    fn bind_top(&mut self, r: &F);
+
+    fn sumcheck_top(&mut self, num_rounds: usize) -> CubicSumcheckProof<F> {
+        self.sumcheck_top_timed(num_rounds).0
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn sumcheck_top_timed(
+        &mut self,
+        num_rounds: usize,
+    ) -> (CubicSumcheckProof<F>, Vec<RoundTiming>) {
+        let mut round_polys = Vec::with_capacity(num_rounds);
+        let mut rs = Vec::with_capacity(num_rounds);
+        let mut timings = Vec::with_capacity(num_rounds);
+
+        for _ in 0..num_rounds {
+            memory::reset_peak();
+
+            let start_time = Instant::now();
+            let evals = self.eval_cubic_top();
+            let eval = start_time.elapsed();
+
+            let start_time = Instant::now();
+            round_polys.push(evals);
+            let r = CubicSumcheckProof::fiat_shamir(evals);
+            rs.push(r);
+            let transcript = start_time.elapsed();
 
-                assert_eq!(plain_proof, gpu_proof);
+            let start_time = Instant::now();
+            self.bind_top(&r);
+            let bind = start_time.elapsed();
+
+            timings.push(RoundTiming {
+                eval,
+                transcript,
+                bind,
+                resident_bytes: memory::current_bytes(),
+                peak_bytes: memory::peak_bytes(),
             });
-            let duration_gpu = start_gpu.elapsed();
-            println!("GPUSumcheck: {:?}", duration_gpu);
         }
 
-        plain_proof.verify(&claim);
+        (CubicSumcheckProof { round_polys, rs }, timings)
+    }
+}
+
+/// Like `sumcheck_top_timed`, but records the field operations of each phase instead of time.
+pub fn sumcheck_top_counted<F: Field, S: CubicSumcheck<Counted<F>> + Send>(
+    sumcheck: &mut S,
+    num_rounds: usize,
+) -> (CubicSumcheckProof<Counted<F>>, Vec<RoundOpCounts>) {
+    let mut round_polys = Vec::with_capacity(num_rounds);
+    let mut rs = Vec::with_capacity(num_rounds);
+    let mut counts = Vec::with_capacity(num_rounds);
+
+    for _ in 0..num_rounds {
+        let (evals, eval) = count_ops(|| sumcheck.eval_cubic_top());
+        round_polys.push(evals);
+        let (r, transcript) = count_ops(|| CubicSumcheckProof::fiat_shamir(evals));
+        rs.push(r);
+        let ((), bind) = count_ops(|| sumcheck.bind_top(&r));
+
+        counts.push(RoundOpCounts {
+            eval,
+            transcript,
+            bind,
+        });
+    }
+
+    (CubicSumcheckProof { round_polys, rs }, counts)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RoundOpCounts {
+    pub eval: OpCounts,
+    pub transcript: OpCounts,
+    pub bind: OpCounts,
+}
+
+impl RoundOpCounts {
+    pub fn total(&self) -> OpCounts {
+        self.eval + self.transcript + self.bind
     }
 }
 
+impl std::iter::Sum for RoundOpCounts {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(RoundOpCounts::default(), |acc, c| RoundOpCounts {
+            eval: acc.eval + c.eval,
+            transcript: acc.transcript + c.transcript,
+            bind: acc.bind + c.bind,
+        })
+    }
+}
+
+/// Wall-clock time spent in each phase of a single sumcheck round, and the process heap if
+/// `memory::TrackingAllocator` is installed (zero otherwise).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RoundTiming {
+    pub eval: Duration,
+    pub transcript: Duration,
+    pub bind: Duration,
+    /// Live heap bytes once the round's bind is done.
+    pub resident_bytes: usize,
+    /// Peak heap bytes during the round.
+    pub peak_bytes: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,6 +510,49 @@ mod tests {
     use crate::sumcheck::gpu::GPUSumcheck;
     use crate::sumcheck::plain::PlainSumcheck;
 
+    /// Challenges of base-field round polynomials spread over the whole extension: no coordinate
+    /// is an affine function of another, and reordered or distinct round polynomials do not share
+    /// a challenge.
+    fn extension_challenges<F: Field>() {
+        use std::collections::HashSet;
+
+        let coords = |round_poly: &[F; 4]| -> Vec<F::BasePrimeField> {
+            fiat_shamir(round_poly)
+                .to_base_prime_field_elements()
+                .collect()
+        };
+        let base = |x: u64| F::from(x);
+        let polys: Vec<[F; 4]> = (0..256u64)
+            .map(|i| [base(i + 1), base(7 * i + 3), base(i * i + 5), base(2)])
+            .collect();
+        let challenges: HashSet<F> = polys.iter().map(|p| fiat_shamir(p)).collect();
+        assert_eq!(challenges.len(), polys.len());
+        let [p, q, s] = [0, 1, 2].map(|i| coords(&polys[i]));
+        for k in 1..F::extension_degree() as usize {
+            assert_ne!((q[k] - p[k]) * (s[0] - p[0]), (s[k] - p[k]) * (q[0] - p[0]));
+        }
+
+        // The product of the evaluations is the same, the challenge is not.
+        let [x, y] = [base(3), base(5)];
+        assert_ne!(fiat_shamir(&[x, y, x, x]), fiat_shamir(&[y, x, x, x]));
+
+        // A challenge already outside the base field is kept.
+        let r = fiat_shamir(&[base(3), base(5), base(7), base(11)]);
+        let round_poly = [r, base(1), base(1), base(1)];
+        let expected = (r + F::from(13u64)) * F::from(29u64);
+        assert_eq!(fiat_shamir(&round_poly), expected);
+    }
+
+    #[test]
+    fn extension_challenges_are_independent() {
+        use crate::field::goldilocks::{GoldilocksExt2, GoldilocksExt4};
+        use crate::field::m31::QM31;
+
+        extension_challenges::<GoldilocksExt2>();
+        extension_challenges::<GoldilocksExt4>();
+        extension_challenges::<QM31>();
+    }
+
     #[test]
     fn plain_sumcheck() {
         let eq = vec![Fr::from(12), Fr::from(13), Fr::from(14), Fr::from(15)];
@@ -171,7 +563,312 @@ mod tests {
 
         let mut plain = PlainSumcheck::new(eq, a, b);
         let proof = plain.sumcheck_top(2);
-        proof.verify(&claim);
+        proof.verify(&claim).unwrap();
+    }
+
+    #[test]
+    fn verify_rejects_tampered_proof() {
+        let eq = vec![Fr::from(12), Fr::from(13), Fr::from(14), Fr::from(15)];
+        let claim = cubic_claim(&eq, &eq, &eq);
+        let proof = Backend::Plain.prove(eq.clone(), eq.clone(), eq);
+
+        let mut bad_claim = proof.clone();
+        bad_claim.round_polys[0].0 += Fr::from(1);
+        assert!(matches!(
+            bad_claim.verify(&claim),
+            Err(SumcheckError::RoundSumMismatch { round: 0, .. })
+        ));
+
+        let mut bad_r = proof.clone();
+        bad_r.rs[1] += Fr::from(1);
+        assert_eq!(
+            bad_r.verify(&claim),
+            Err(SumcheckError::ChallengeMismatch { round: 1 })
+        );
+
+        let mut short = proof;
+        short.rs.pop();
+        assert_eq!(
+            short.verify(&claim),
+            Err(SumcheckError::RoundCountMismatch {
+                round_polys: 2,
+                rs: 1
+            })
+        );
+    }
+
+    #[test]
+    fn verify_factors_evaluates_public_polynomials() {
+        use crate::poly::plain::rand_vec;
+        use crate::poly::structured::{EqPolynomial, IdentityPolynomial, LtPolynomial};
+
+        let log_size = 6;
+        let eq = EqPolynomial::new(rand_vec::<Fr>(8)[..log_size].to_vec());
+        let id = IdentityPolynomial::new(log_size);
+        let lt = LtPolynomial::new(rand_vec::<Fr>(8)[2..2 + log_size].to_vec());
+        let a = DensePolynomial::new(rand_vec::<Fr>(1 << log_size));
+
+        let prove = |eq: &dyn StructuredPolynomial<Fr>, b: &dyn StructuredPolynomial<Fr>| {
+            let (eq, b) = (eq.evals().Z, b.evals().Z);
+            let claim = cubic_claim(&eq, &a.Z, &b);
+            let proof = PlainSumcheck::new(eq, a.Z.clone(), b).sumcheck_top(log_size);
+            (proof, claim)
+        };
+
+        let factors = |a_eval, b| [Factor::Public(&eq), Factor::Claimed(a_eval), b];
+
+        let (proof, claim) = prove(&eq, &id);
+        let a_eval = a.evaluate(&proof.rs);
+        assert_eq!(
+            proof.verify_factors(&claim, factors(a_eval, Factor::Public(&id))),
+            Ok(())
+        );
+        for wrong in [
+            factors(a_eval + Fr::from(1), Factor::Public(&id)),
+            factors(a_eval, Factor::Public(&lt)),
+        ] {
+            assert!(matches!(
+                proof.verify_factors(&claim, wrong),
+                Err(SumcheckError::FinalClaimMismatch { .. })
+            ));
+        }
+
+        let (proof, claim) = prove(&eq, &lt);
+        let a_eval = a.evaluate(&proof.rs);
+        assert_eq!(
+            proof.verify_factors(&claim, factors(a_eval, Factor::Public(&lt))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn ragged_inputs_prove_like_padded() {
+        use crate::poly::plain::rand_vec;
+
+        let evals = rand_vec::<Fr>(64);
+        let reversed: Vec<Fr> = evals.iter().rev().copied().collect();
+        for (eq_len, a_len, b_len) in [(64, 37, 33), (48, 64, 5), (21, 17, 20), (3, 3, 3), (1, 1, 1)] {
+            let (eq, a, b) = (&evals[..eq_len], &reversed[..a_len], &evals[..b_len]);
+            let num_rounds = eq_len.max(a_len).max(b_len).next_power_of_two().ilog2() as usize;
+            let pad = |v: &[Fr]| {
+                let mut v = v.to_vec();
+                v.resize(1 << num_rounds, Fr::from(0));
+                v
+            };
+
+            let proof =
+                PlainSumcheck::new(eq.to_vec(), a.to_vec(), b.to_vec()).sumcheck_top(num_rounds);
+            let padded = PlainSumcheck::new(pad(eq), pad(a), pad(b)).sumcheck_top(num_rounds);
+            assert_eq!(proof, padded);
+            proof.verify(&cubic_claim(eq, a, b)).unwrap();
+            for &backend in Backend::ALL {
+                assert_eq!(backend.prove(eq.to_vec(), a.to_vec(), b.to_vec()), padded);
+            }
+        }
+    }
+
+    #[test]
+    fn plain_op_counts() {
+        use crate::field::counted::counted_vec;
+
+        let log_size = 4;
+        let evals = crate::poly::plain::rand_vec::<Fr>(3 << log_size);
+        let [eq, a, b] = [0, 1, 2].map(|k| evals[k << log_size..(k + 1) << log_size].to_vec());
+        let claim = Counted(cubic_claim(&eq, &a, &b));
+        let reference = PlainSumcheck::new(eq.clone(), a.clone(), b.clone()).sumcheck_top(log_size);
+        let [eq, a, b] = [eq, a, b].map(|z| counted_vec(&z));
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let (proof, rounds) = pool.install(|| {
+            let mut plain = PlainSumcheck::new(eq, a, b);
+            sumcheck_top_counted(&mut plain, log_size)
+        });
+
+        for (round, counts) in rounds.iter().enumerate() {
+            let n = 1u64 << (log_size - round - 1);
+            // Per pair: 2 muls for each of the 4 evaluations, 3 subs for the slopes and 6 adds to
+            // step to 2 and 3. The reduction adds 4 per pair; rounds this small run sequentially,
+            // so rayon does not split the range and add partial sums.
+            let reduce_adds = 4 * n;
+            assert_eq!(
+                counts.eval,
+                OpCounts {
+                    mul: 8 * n,
+                    add: 6 * n + reduce_adds,
+                    sub: 3 * n,
+                    reduction: 8 * n,
+                    ..OpCounts::default()
+                },
+                "eval round {round}"
+            );
+            // low + r * (high - low) on each of eq, a and b.
+            assert_eq!(
+                counts.bind,
+                OpCounts {
+                    mul: 3 * n,
+                    add: 3 * n,
+                    sub: 3 * n,
+                    reduction: 3 * n,
+                    ..OpCounts::default()
+                },
+                "bind round {round}"
+            );
+            // Product of the 4 evaluations, + 13, * 29, with 13 and 29 converted into the field.
+            assert_eq!(
+                counts.transcript,
+                OpCounts {
+                    mul: 4,
+                    add: 1,
+                    reduction: 6,
+                    ..OpCounts::default()
+                }
+            );
+        }
+        let rs: Vec<Fr> = proof.rs.iter().map(|r| r.0).collect();
+        assert_eq!(rs, reference.rs);
+        let total: RoundOpCounts = rounds.iter().copied().sum();
+        assert_eq!(total.eval.mul, 8 * 15);
+        assert_eq!(total.total().sub, 6 * 15);
+
+        let (result, verify) = count_ops(|| proof.verify(&claim));
+        result.unwrap();
+        // Per round: s(0) + s(1), the transcript, and 4 Lagrange basis polynomials that each take
+        // 4 muls, 6 subs, 9 integer conversions and a division, followed by 4 muls and 3 adds.
+        let rounds = log_size as u64;
+        assert_eq!(
+            verify,
+            OpCounts {
+                mul: 28 * rounds,
+                square: 0,
+                add: 5 * rounds,
+                sub: 24 * rounds,
+                inverse: 4 * rounds,
+                reduction: 66 * rounds,
+            }
+        );
+    }
+
+    #[test]
+    fn proof_serialization_roundtrip() {
+        let eq = vec![Fr::from(12), Fr::from(13), Fr::from(14), Fr::from(15)];
+        let proof = Backend::Simd.prove(eq.clone(), eq.clone(), eq);
+
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        let decoded = CubicSumcheckProof::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(proof, decoded);
+    }
+
+    #[test]
+    fn round_strategies_produce_identical_proofs() {
+        let eq = crate::poly::plain::rand_vec::<Fr>(1 << 8);
+        let reference = Backend::Plain.prove(eq.clone(), eq.clone(), eq.clone());
+
+        let strategies = [
+            RoundStrategy::PARALLEL,
+            RoundStrategy::new(usize::MAX, usize::MAX).unwrap(),
+            RoundStrategy::new(0, usize::MAX).unwrap(),
+            RoundStrategy::new(1 << 2, 1 << 5).unwrap(),
+        ];
+        for strategy in strategies {
+            let options = ProverOptions {
+                strategy,
+                ..ProverOptions::default()
+            };
+            for backend in [Backend::Plain, Backend::Simd, Backend::Interleaved] {
+                let (proof, _) = backend.prove_timed(eq.clone(), eq.clone(), eq.clone(), options);
+                assert_eq!(proof, reference, "{backend} / {strategy:?}");
+            }
+        }
+
+        let strategy = RoundStrategy::new(1 << 2, 1 << 5).unwrap();
+        assert_eq!(strategy.execution(3), Execution::Sequential);
+        assert_eq!(strategy.execution(4), Execution::Reduced);
+        assert_eq!(strategy.execution(1 << 5), Execution::Parallel);
+        assert!(RoundStrategy::new(8, 4).is_err());
+    }
+
+    #[test]
+    fn simd_eval_matches_plain_for_any_chunk_size() {
+        use crate::sumcheck::simd::SIMDSumcheck;
+
+        let evals = crate::poly::plain::rand_vec::<Fr>(1 << 6);
+        let expected =
+            PlainSumcheck::new(evals.clone(), evals.clone(), evals.clone()).eval_cubic_top();
+        let simd = SIMDSumcheck::new(evals.clone(), evals.clone(), evals);
+        // Growing and shrinking chunks reuse the same scratch buffers.
+        for chunk_size in [1, 3, 32, 7, 5, 64] {
+            assert_eq!(
+                simd.eval_cubic_top_chunked(chunk_size),
+                expected,
+                "chunk {chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn simd_sumcheck_on_other_fields() {
+        use crate::field::counted::counted_vec;
+        use crate::sumcheck::simd::SIMDSumcheck;
+        use ark_bn254::Fq;
+
+        let evals = crate::poly::plain::rand_vec::<Fq>(1 << 6);
+        let claim = cubic_claim(&evals, &evals, &evals);
+        let proof = SIMDSumcheck::new(evals.clone(), evals.clone(), evals.clone()).sumcheck_top(6);
+        assert_eq!(
+            proof,
+            PlainSumcheck::new(evals.clone(), evals.clone(), evals).sumcheck_top(6)
+        );
+        assert!(proof.verify(&claim).is_ok());
+
+        let evals = counted_vec(&crate::poly::plain::rand_vec::<Fr>(1 << 6));
+        let proof = SIMDSumcheck::new(evals.clone(), evals.clone(), evals.clone()).sumcheck_top(6);
+        assert_eq!(
+            proof,
+            PlainSumcheck::new(evals.clone(), evals.clone(), evals).sumcheck_top(6)
+        );
+
+        simd_matches_plain::<crate::field::goldilocks::Goldilocks>();
+        simd_matches_plain::<crate::field::m31::M31>();
+    }
+
+    fn simd_matches_plain<F: crate::field::vec_ops::FieldVecOps + ark_ff::PrimeField>() {
+        use crate::sumcheck::simd::SIMDSumcheck;
+
+        let evals = crate::poly::plain::rand_vec::<F>(1 << 6);
+        let claim = cubic_claim(&evals, &evals, &evals);
+        let proof = SIMDSumcheck::new(evals.clone(), evals.clone(), evals.clone()).sumcheck_top(6);
+        assert_eq!(
+            proof,
+            PlainSumcheck::new(evals.clone(), evals.clone(), evals).sumcheck_top(6)
+        );
+        assert!(proof.verify(&claim).is_ok());
+    }
+
+    #[test]
+    fn shrink_releases_capacity_without_changing_proof() {
+        let eq = crate::poly::plain::rand_vec::<Fr>(1 << 6);
+        let reference = Backend::Plain.prove(eq.clone(), eq.clone(), eq.clone());
+
+        for shrink in [Shrink::Fit, Shrink::Reallocate] {
+            let options = ProverOptions {
+                shrink,
+                ..ProverOptions::default()
+            };
+            for backend in [Backend::Plain, Backend::Simd, Backend::Interleaved] {
+                let (proof, _) = backend.prove_timed(eq.clone(), eq.clone(), eq.clone(), options);
+                assert_eq!(proof, reference, "{backend} / {shrink}");
+            }
+
+            let mut evals = eq.clone();
+            evals.truncate(1 << 3);
+            shrink.apply(&mut evals);
+            assert_eq!(evals, eq[..1 << 3]);
+            assert!(evals.capacity() < 1 << 6, "{shrink}");
+        }
     }
 
     #[cfg(feature = "gpu")]
@@ -185,7 +882,7 @@ mod tests {
 
         let mut sumcheck = GPUSumcheck::new(eq, a, b);
         let proof = sumcheck.sumcheck_top(2);
-        proof.verify(&claim);
+        proof.verify(&claim).unwrap();
     }
 
     #[cfg(feature = "gpu")]
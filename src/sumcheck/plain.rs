@@ -1,67 +1,153 @@
+use crate::field::lazy::LazySum;
 use crate::poly::plain::DensePolynomial;
-use crate::sumcheck::CubicSumcheck;
+use crate::sumcheck::{num_vars, CubicSumcheck, ProverOptions};
 use ark_bn254::Fr;
-use ark_std::Zero;
+use ark_ff::Field;
 use rayon::prelude::*;
 
-pub struct PlainSumcheck {
-    eq: DensePolynomial<Fr>,
-    a: DensePolynomial<Fr>,
-    b: DensePolynomial<Fr>,
+pub struct PlainSumcheck<F: LazySum = Fr> {
+    eq: DensePolynomial<F>,
+    a: DensePolynomial<F>,
+    b: DensePolynomial<F>,
+    options: ProverOptions,
 }
 
-impl CubicSumcheck for PlainSumcheck {
-    fn new(eq: Vec<Fr>, a: Vec<Fr>, b: Vec<Fr>) -> Self {
-        let eq = DensePolynomial::new(eq);
-        let a = DensePolynomial::new(a);
-        let b = DensePolynomial::new(b);
+impl<F: LazySum> PlainSumcheck<F> {
+    /// The current eq, a and b.
+    pub(crate) fn polys(&self) -> [&DensePolynomial<F>; 3] {
+        [&self.eq, &self.a, &self.b]
+    }
 
-        Self { eq, a, b }
+    pub(crate) fn options(&self) -> ProverOptions {
+        self.options
     }
+}
+
+/// The number of pairs of a round over `eq`, which needs a variable left to bind.
+pub(crate) fn half_len<F: Field>(eq: &DensePolynomial<F>) -> usize {
+    assert!(eq.num_vars() > 0, "no variable left to bind");
+    1 << (eq.num_vars() - 1)
+}
+
+/// Adds one pair's eq * a * b at 0, 1, 2 and 3 to the round sums, with each factor at
+/// low + k * (high - low), eq * a reduced and the product with b accumulated lazily.
+#[inline(always)]
+pub(crate) fn accumulate_pair<F: LazySum>(
+    evals: &mut [F::Acc; 4],
+    (eq_low, eq_high): (F, F),
+    (a_low, a_high): (F, F),
+    (b_low, b_high): (F, F),
+) {
+    F::mul_add(&mut evals[0], &(eq_low * a_low), &b_low);
+    F::mul_add(&mut evals[1], &(eq_high * a_high), &b_high);
+
+    let eq_m = eq_high - eq_low;
+    let a_m = a_high - a_low;
+    let b_m = b_high - b_low;
+
+    let eq_2 = eq_high + eq_m;
+    let a_2 = a_high + a_m;
+    let b_2 = b_high + b_m;
+    F::mul_add(&mut evals[2], &(eq_2 * a_2), &b_2);
+
+    let eq_3 = eq_2 + eq_m;
+    let a_3 = a_2 + a_m;
+    let b_3 = b_2 + b_m;
+    F::mul_add(&mut evals[3], &(eq_3 * a_3), &b_3);
+}
 
-    fn eval_cubic_top(&mut self) -> (Fr, Fr, Fr, Fr) {
-        let len = self.eq.Z.len();
-        assert_eq!(self.a.Z.len(), len);
-        assert_eq!(self.b.Z.len(), len);
-        let n = len / 2;
-
-        // low + r * (high - low)
-        let (eval_0, eval_1, eval_2, eval_3) = (0..n)
-            .into_par_iter()
-            .map(|i| {
-                let low = i;
-                let high = n + i;
-
-                let eval_0: Fr = self.eq[low] * self.a[low] * self.b[low];
-                let eval_1: Fr = self.eq[high] * self.a[high] * self.b[high];
-
-                let eq_m: Fr = self.eq[high] - self.eq[low];
-                let a_m: Fr = self.a[high] - self.a[low];
-                let b_m: Fr = self.b[high] - self.b[low];
-
-                let eq_2 = self.eq[high] + eq_m;
-                let a_2 = self.a[high] + a_m;
-                let b_2 = self.b[high] + b_m;
-                let eval_2 = eq_2 * a_2 * b_2;
-
-                let eq_3 = eq_2 + eq_m;
-                let a_3 = a_2 + a_m;
-                let b_3 = b_2 + b_m;
-                let eval_3 = eq_3 * a_3 * b_3;
-
-                (eval_0, eval_1, eval_2, eval_3)
-            })
-            .reduce(
-                || (Fr::zero(), Fr::zero(), Fr::zero(), Fr::zero()),
-                |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3),
-            );
+impl<F: LazySum> CubicSumcheck<F> for PlainSumcheck<F> {
+    /// eq, a and b may have any lengths, with the missing tails zero up to the next power of two
+    /// of the longest. The proof is that of the zero-padded polynomials.
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        let num_vars = num_vars([&eq, &a, &b]);
+        let eq = DensePolynomial::new_padded(eq, num_vars);
+        let a = DensePolynomial::new_padded(a, num_vars);
+        let b = DensePolynomial::new_padded(b, num_vars);
 
+        Self {
+            eq,
+            a,
+            b,
+            options: ProverOptions::default(),
+        }
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    fn eval_cubic_top(&mut self) -> (F, F, F, F) {
+        let n = half_len(&self.eq);
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        let (eq, a, b) = (&self.eq, &self.a, &self.b);
+        let lens = [eq.len(), a.len(), b.len()];
+        // Pairs with all six entries stored are read straight from the slices, the ragged tail
+        // through `get`, and pairs past every stored entry are zero and add nothing.
+        let dense = lens.iter().min().unwrap().saturating_sub(n);
+        let live = n.min(*lens.iter().max().unwrap());
+        let merge = |x: [F::Acc; 4], y: [F::Acc; 4]| [0, 1, 2, 3].map(|k| F::merge(x[k], y[k]));
+
+        let [(eq_low, eq_high), (a_low, a_high), (b_low, b_high)] =
+            [eq, a, b].map(|p| (&p.Z[..dense], &p.Z[n.min(p.len())..][..dense]));
+        let mut evals = eq_low
+            .par_iter()
+            .zip(eq_high)
+            .zip(a_low)
+            .zip(a_high)
+            .zip(b_low)
+            .zip(b_high)
+            .with_min_len(min_len)
+            .fold(
+                || [F::acc_zero(); 4],
+                |mut evals, (((((eq_low, eq_high), a_low), a_high), b_low), b_high)| {
+                    accumulate_pair::<F>(
+                        &mut evals,
+                        (*eq_low, *eq_high),
+                        (*a_low, *a_high),
+                        (*b_low, *b_high),
+                    );
+                    evals
+                },
+            )
+            .reduce_with(merge)
+            .unwrap_or([F::acc_zero(); 4]);
+
+        if dense < live {
+            let tail = (dense..live)
+                .into_par_iter()
+                .with_min_len(min_len)
+                .fold(
+                    || [F::acc_zero(); 4],
+                    |mut evals, i| {
+                        let (low, high) = (i, n + i);
+                        accumulate_pair::<F>(
+                            &mut evals,
+                            (eq.get(low), eq.get(high)),
+                            (a.get(low), a.get(high)),
+                            (b.get(low), b.get(high)),
+                        );
+                        evals
+                    },
+                )
+                .reduce_with(merge)
+                .unwrap();
+            evals = merge(evals, tail);
+        }
+
+        let [eval_0, eval_1, eval_2, eval_3] = evals.map(F::reduce);
         (eval_0, eval_1, eval_2, eval_3)
     }
 
-    fn bind_top(&mut self, r: &Fr) {
-        self.eq.bound_poly_var_top_par(r);
-        self.a.bound_poly_var_top_par(r);
-        self.b.bound_poly_var_top_par(r);
+    fn bind_top(&mut self, r: &F) {
+        let n = half_len(&self.eq);
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        self.eq.bound_poly_var_top_min_len(r, min_len);
+        self.a.bound_poly_var_top_min_len(r, min_len);
+        self.b.bound_poly_var_top_min_len(r, min_len);
+
+        self.options.shrink.apply(&mut self.eq.Z);
+        self.options.shrink.apply(&mut self.a.Z);
+        self.options.shrink.apply(&mut self.b.Z);
     }
 }
@@ -0,0 +1,133 @@
+use ark_std::Zero;
+use std::mem::MaybeUninit;
+use std::sync::Mutex;
+
+/// Scratch buffers for the chunked SIMD kernels, one slot per rayon thread. Slots grow to the
+/// largest request and are then reused across chunks and rounds, so a sumcheck allocates them
+/// once instead of once per chunk.
+pub struct ScratchArena<T> {
+    slots: Vec<Mutex<Slot<T>>>,
+}
+
+struct Slot<T> {
+    buf: Vec<MaybeUninit<T>>,
+    /// `buf[..initialized]` has been written.
+    initialized: usize,
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Slot {
+            buf: Vec::new(),
+            initialized: 0,
+        }
+    }
+}
+
+impl<T: Copy + Zero> Slot<T> {
+    fn take(&mut self, len: usize) -> &mut [T] {
+        if len > self.buf.len() {
+            self.buf.resize_with(len, MaybeUninit::uninit);
+        }
+        for slot in &mut self.buf[self.initialized.min(len)..len] {
+            slot.write(T::zero());
+        }
+        self.initialized = self.initialized.max(len);
+        // SAFETY: the first `len` elements were written above or by an earlier call, and
+        // `MaybeUninit<T>` has the same layout as `T`.
+        unsafe { std::slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast::<T>(), len) }
+    }
+}
+
+impl<T: Copy + Zero> Default for ScratchArena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Zero> ScratchArena<T> {
+    /// One empty slot per thread of the current rayon pool.
+    pub fn new() -> Self {
+        ScratchArena {
+            slots: (0..rayon::current_num_threads())
+                .map(|_| Mutex::default())
+                .collect(),
+        }
+    }
+
+    /// Runs `f` on `K` disjoint buffers of `len` elements from the calling thread's slot. The
+    /// buffers hold whatever the previous user left in them.
+    ///
+    /// The buffers are moved out of the slot and the lock released before `f` runs, so `f` may
+    /// call back into rayon: a call that finds its slot empty, because it runs on a thread whose
+    /// slot is out or on a thread of another pool sharing the slot, allocates buffers of its own,
+    /// and the larger of the two is kept when both are returned.
+    pub fn with<const K: usize, R>(&self, len: usize, f: impl FnOnce([&mut [T]; K]) -> R) -> R {
+        let index = rayon::current_thread_index().unwrap_or(0) % self.slots.len();
+        let mut slot = std::mem::take(&mut *self.slots[index].lock().unwrap());
+        let mut buffers = slot.take(K * len).chunks_exact_mut(len.max(1));
+        let result = f(std::array::from_fn(|_| match buffers.next() {
+            Some(buffer) => buffer,
+            None => &mut [],
+        }));
+        let mut current = self.slots[index].lock().unwrap();
+        if slot.buf.len() > current.buf.len() {
+            *current = slot;
+        }
+        result
+    }
+
+    /// Elements reserved across all slots.
+    pub fn capacity(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|slot| slot.lock().unwrap().buf.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use rayon::prelude::*;
+
+    #[test]
+    fn reuses_slots_across_calls() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let arena = ScratchArena::<Fr>::new();
+            (0..64).into_par_iter().for_each(|i| {
+                arena.with(16, |[x, y]: [&mut [Fr]; 2]| {
+                    assert_eq!((x.len(), y.len()), (16, 16));
+                    x.fill(Fr::from(i as u64));
+                    y.copy_from_slice(x);
+                    assert!(y.iter().all(|v| *v == Fr::from(i as u64)));
+                });
+            });
+            let capacity = arena.capacity();
+            assert!((32..=4 * 32).contains(&capacity));
+
+            // Smaller requests reuse the existing buffers.
+            (0..64)
+                .into_par_iter()
+                .for_each(|_| arena.with(8, |[_, _]: [&mut [Fr]; 2]| {}));
+            assert_eq!(arena.capacity(), capacity);
+
+            // Kernels that call back into rayon while holding buffers neither deadlock nor
+            // share buffers.
+            (0..16).into_par_iter().for_each(|i| {
+                arena.with(8, |[x]: [&mut [Fr]; 1]| {
+                    x.fill(Fr::from(i as u64));
+                    (0..16).into_par_iter().for_each(|j| {
+                        arena.with(4, |[y]: [&mut [Fr]; 1]| y.fill(Fr::from(j as u64)));
+                    });
+                    assert!(x.iter().all(|v| *v == Fr::from(i as u64)));
+                });
+            });
+        });
+    }
+}
@@ -1,72 +1,86 @@
+use crate::field::vec_ops::FieldVecOps;
 use crate::poly::plain::DensePolynomial;
-use crate::sumcheck::CubicSumcheck;
+use crate::sumcheck::scratch::ScratchArena;
+use crate::sumcheck::tuning::{self, Kernel};
+use crate::sumcheck::{pad_dense, CubicSumcheck, Execution, ProverOptions};
 use ark_bn254::Fr;
 use ark_ff::PrimeField;
-use ark_std::Zero;
 use rayon::prelude::*;
 
-pub struct SIMDPolynomial {
-    pub Z: Vec<Fr>,
+pub struct SIMDPolynomial<F: FieldVecOps = Fr> {
+    pub Z: Vec<F>,
 }
 
-impl SIMDPolynomial {
-    pub fn bound_poly_var_top_par(&mut self, r: &Fr) {
+impl<F: FieldVecOps> SIMDPolynomial<F> {
+    pub fn bound_poly_var_top_par(&mut self, r: &F) {
+        let chunk_size = tuning::chunk_size(Kernel::Bind, self.Z.len() / 2);
+        self.bound_poly_var_top_chunked(r, chunk_size);
+    }
+
+    /// `bound_poly_var_top_par` with an explicit chunk size instead of the tuned one.
+    pub fn bound_poly_var_top_chunked(&mut self, r: &F, chunk_size: usize) {
         let n = self.Z.len() / 2;
         let (left, right) = self.Z.split_at_mut(n);
 
-        use vectorized_fields::*;
-
-        let rayon_threads = rayon::current_num_threads();
-        let chunk_size = (n / rayon_threads / 16) + 2; // Non-zero + better work-stealing
-        let chunk_size = std::cmp::min(chunk_size, 512);
-
-        let r = vec![r.clone(); chunk_size];
+        let r = vec![*r; chunk_size];
         left.par_chunks_mut(chunk_size)
             .zip(right.par_chunks_mut(chunk_size))
             .for_each(|(left_chunk, right_chunk)| {
                 let chunk_size = left_chunk.len();
 
-                sub_vec_inplace_bn254(right_chunk, left_chunk);
-                mul_vec_inplace_bn254(right_chunk, &r[..chunk_size]);
-                add_vec_inplace_bn254(left_chunk, right_chunk);
+                F::sub_vec_inplace(right_chunk, left_chunk);
+                F::mul_vec_inplace(right_chunk, &r[..chunk_size]);
+                F::add_vec_inplace(left_chunk, right_chunk);
             });
 
         self.Z.truncate(n);
     }
 }
 
-pub struct SIMDSumcheck {
-    eq: SIMDPolynomial,
-    a: SIMDPolynomial,
-    b: SIMDPolynomial,
+pub struct SIMDSumcheck<F: FieldVecOps = Fr> {
+    eq: SIMDPolynomial<F>,
+    a: SIMDPolynomial<F>,
+    b: SIMDPolynomial<F>,
+    options: ProverOptions,
+    scratch: ScratchArena<F>,
 }
 
-impl CubicSumcheck for SIMDSumcheck {
-    fn new(eq: Vec<Fr>, a: Vec<Fr>, b: Vec<Fr>) -> Self {
-        let eq = SIMDPolynomial { Z: eq };
-        let a = SIMDPolynomial { Z: a };
-        let b = SIMDPolynomial { Z: b };
+impl<F: FieldVecOps> SIMDSumcheck<F> {
+    /// The execution and chunk size of this round: the tuned profile's where it covers the size,
+    /// the strategy's elsewhere.
+    fn chunking(&self, kernel: Kernel) -> (Execution, usize) {
+        let n = self.eq.Z.len() / 2;
+        let threads = rayon::current_num_threads();
+        tuning::round_chunking(
+            tuning::profile(),
+            &self.options.strategy,
+            kernel,
+            n,
+            threads,
+        )
+    }
 
-        Self { eq, a, b }
+    /// `eval_cubic_top` with an explicit chunk size instead of the tuned one.
+    pub fn eval_cubic_top_chunked(&self, chunk_size: usize) -> (F, F, F, F) {
+        self.eval_cubic_top_with(chunk_size, &self.scratch)
     }
 
-    #[tracing::instrument(skip_all)]
-    fn eval_cubic_top(&mut self) -> (Fr, Fr, Fr, Fr) {
+    /// `eval_cubic_top_chunked` with its chunk buffers taken from `scratch`.
+    pub fn eval_cubic_top_with(
+        &self,
+        chunk_size: usize,
+        scratch: &ScratchArena<F>,
+    ) -> (F, F, F, F) {
         let len = self.eq.Z.len();
         assert_eq!(self.a.Z.len(), len);
         assert_eq!(self.b.Z.len(), len);
         let n = len / 2;
 
-        use vectorized_fields::*;
-
-        let rayon_threads = rayon::current_num_threads();
-        let chunk_size = (n / rayon_threads / 32) + 2; // Non-zero + better work-stealing
-        let chunk_size = std::cmp::min(chunk_size, 512);
         let (eq_low, eq_high) = self.eq.Z.split_at(n);
         let (a_low, a_high) = self.a.Z.split_at(n);
         let (b_low, b_high) = self.b.Z.split_at(n);
 
-        let (eval_0, eval_1, eval_2, eval_3) = eq_low
+        let evals = eq_low
             .par_chunks(chunk_size)
             .zip(eq_high.par_chunks(chunk_size))
             .zip(a_low.par_chunks(chunk_size))
@@ -75,67 +89,97 @@ impl CubicSumcheck for SIMDSumcheck {
             .zip(b_high.par_chunks(chunk_size))
             .map(|(((((eq_low, eq_high), a_low), a_high), b_low), b_high)| {
                 let chunk_size = eq_low.len();
-                let mut buff = unsafe_alloc_vec(chunk_size);
-                mul_vec_bn254(eq_low, a_low, &mut buff);
-                let eval_0 = inner_product_bn254(&buff, b_low);
-                mul_vec_bn254(eq_high, a_high, &mut buff);
-                let eval_1 = inner_product_bn254(&buff, b_high);
-
-                let mut eq_m = unsafe_alloc_vec(chunk_size);
-                let mut a_m = unsafe_alloc_vec(chunk_size);
-                let mut b_m = unsafe_alloc_vec(chunk_size);
-                sub_vec_bn254(eq_high, eq_low, &mut eq_m);
-                sub_vec_bn254(a_high, a_low, &mut a_m);
-                sub_vec_bn254(b_high, b_low, &mut b_m);
-
-                // 2
-                let mut eq_2 = unsafe_alloc_vec(chunk_size);
-                let mut a_2 = unsafe_alloc_vec(chunk_size);
-                let mut b_2 = unsafe_alloc_vec(chunk_size);
-                add_vec_bn254(&eq_high, &eq_m, &mut eq_2);
-                add_vec_bn254(&a_high, &a_m, &mut a_2);
-                add_vec_bn254(&b_high, &b_m, &mut b_2);
-                mul_vec_bn254(&eq_2, &a_2, &mut buff);
-                let eval_2 = inner_product_bn254(&buff, &b_2);
-
-                // 3
-                add_vec_inplace_bn254(&mut eq_2, &eq_m);
-                add_vec_inplace_bn254(&mut a_2, &a_m);
-                add_vec_inplace_bn254(&mut b_2, &b_m);
-                mul_vec_inplace_bn254(&mut eq_2, &a_2);
-                let eval_3 = inner_product_bn254(&eq_2, &b_2);
-
-                (eval_0, eval_1, eval_2, eval_3)
+                scratch.with(
+                    chunk_size,
+                    |[buff, eq_m, a_m, b_m, eq_2, a_2, b_2]: [&mut [F]; 7]| {
+                        F::mul_vec(eq_low, a_low, buff);
+                        let eval_0 = F::inner_product(buff, b_low);
+                        F::mul_vec(eq_high, a_high, buff);
+                        let eval_1 = F::inner_product(buff, b_high);
+
+                        F::sub_vec(eq_high, eq_low, eq_m);
+                        F::sub_vec(a_high, a_low, a_m);
+                        F::sub_vec(b_high, b_low, b_m);
+
+                        // 2
+                        F::add_vec(eq_high, eq_m, eq_2);
+                        F::add_vec(a_high, a_m, a_2);
+                        F::add_vec(b_high, b_m, b_2);
+                        F::mul_vec(eq_2, a_2, buff);
+                        let eval_2 = F::inner_product(buff, b_2);
+
+                        // 3
+                        F::add_vec_inplace(eq_2, eq_m);
+                        F::add_vec_inplace(a_2, a_m);
+                        F::add_vec_inplace(b_2, b_m);
+                        F::mul_vec_inplace(eq_2, a_2);
+                        let eval_3 = F::inner_product(eq_2, b_2);
+
+                        [eval_0, eval_1, eval_2, eval_3]
+                    },
+                )
             })
-            .reduce(
-                || (Fr::zero(), Fr::zero(), Fr::zero(), Fr::zero()),
-                |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3),
-            );
+            .reduce_with(|a, b| [0, 1, 2, 3].map(|k| F::merge(a[k], b[k])))
+            .unwrap_or([F::acc_zero(); 4]);
 
+        let [eval_0, eval_1, eval_2, eval_3] = evals.map(F::reduce);
         (eval_0, eval_1, eval_2, eval_3)
     }
+}
+
+impl<F: FieldVecOps> CubicSumcheck<F> for SIMDSumcheck<F> {
+    /// eq, a and b may have any lengths. The kernels run on whole vectors, so missing tails are
+    /// written out as zeros up to the next power of two of the longest.
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        let [eq, a, b] = pad_dense([eq, a, b]);
+        let eq = SIMDPolynomial { Z: eq };
+        let a = SIMDPolynomial { Z: a };
+        let b = SIMDPolynomial { Z: b };
+
+        Self {
+            eq,
+            a,
+            b,
+            options: ProverOptions::default(),
+            scratch: ScratchArena::new(),
+        }
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        Self { options, ..self }
+    }
 
     #[tracing::instrument(skip_all)]
-    fn bind_top(&mut self, r: &Fr) {
-        rayon::join(
-            || self.eq.bound_poly_var_top_par(r),
-            || {
-                rayon::join(
-                    || self.a.bound_poly_var_top_par(r),
-                    || self.b.bound_poly_var_top_par(r),
-                )
-            },
-        );
+    fn eval_cubic_top(&mut self) -> (F, F, F, F) {
+        let (_, chunk_size) = self.chunking(Kernel::Eval);
+        self.eval_cubic_top_chunked(chunk_size)
     }
-}
 
-#[tracing::instrument(skip_all)]
-pub fn unsafe_alloc_vec(size: usize) -> Vec<Fr> {
-    let mut vec = Vec::with_capacity(size);
-    unsafe {
-        vec.set_len(size);
+    #[tracing::instrument(skip_all)]
+    fn bind_top(&mut self, r: &F) {
+        match self.chunking(Kernel::Bind) {
+            (Execution::Parallel, chunk_size) => {
+                rayon::join(
+                    || self.eq.bound_poly_var_top_chunked(r, chunk_size),
+                    || {
+                        rayon::join(
+                            || self.a.bound_poly_var_top_chunked(r, chunk_size),
+                            || self.b.bound_poly_var_top_chunked(r, chunk_size),
+                        )
+                    },
+                );
+            }
+            (_, chunk_size) => {
+                self.eq.bound_poly_var_top_chunked(r, chunk_size);
+                self.a.bound_poly_var_top_chunked(r, chunk_size);
+                self.b.bound_poly_var_top_chunked(r, chunk_size);
+            }
+        }
+
+        self.options.shrink.apply(&mut self.eq.Z);
+        self.options.shrink.apply(&mut self.a.Z);
+        self.options.shrink.apply(&mut self.b.Z);
     }
-    vec
 }
 
 #[tracing::instrument(skip_all)]
@@ -0,0 +1,299 @@
+use crate::field::goldilocks::{Goldilocks, GoldilocksExt2, GoldilocksExt4};
+use crate::field::kernels::Isa;
+use crate::field::lazy::LazySum;
+use crate::field::m31::{M31, QM31};
+use crate::field::small::{MulBase, PackedFp, PackedSum, SmallField, LANES};
+use crate::poly::plain::DensePolynomial;
+use crate::sumcheck::plain::PlainSumcheck;
+use crate::sumcheck::{CubicSumcheck, ProverOptions};
+use ark_ff::PrimeField;
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Sumcheck over a small prime field `B` with challenges from its extension `E`. Started with
+/// `from_base`, eq, a and b stay base-field values until the first bind, so the first round
+/// works on single words, `LANES` pairs at a time in `PackedFp`. Binding them with an extension
+/// challenge moves the prover to `E` for the remaining rounds.
+pub struct SmallFieldSumcheck<B: SmallField, E: LazySum + MulBase<BasePrimeField = B>> {
+    state: State<B, E>,
+}
+
+enum State<B: LazySum, E: LazySum> {
+    Base(PlainSumcheck<B>),
+    Ext(PlainSumcheck<E>),
+}
+
+pub type GoldilocksSumcheck = SmallFieldSumcheck<Goldilocks, GoldilocksExt2>;
+pub type GoldilocksExt4Sumcheck = SmallFieldSumcheck<Goldilocks, GoldilocksExt4>;
+pub type M31Sumcheck = SmallFieldSumcheck<M31, QM31>;
+
+impl<B, E> SmallFieldSumcheck<B, E>
+where
+    B: SmallField,
+    E: LazySum + MulBase<BasePrimeField = B>,
+{
+    /// Starts from base-field eq, a and b.
+    pub fn from_base(eq: Vec<B>, a: Vec<B>, b: Vec<B>) -> Self {
+        SmallFieldSumcheck {
+            state: State::Base(PlainSumcheck::new(eq, a, b)),
+        }
+    }
+
+    /// Whether eq, a and b are still base-field values.
+    pub fn in_base_field(&self) -> bool {
+        matches!(self.state, State::Base(_))
+    }
+}
+
+/// Blocks of `LANES` pairs per task in the packed first round.
+const TASK_BLOCKS: usize = 1 << 9;
+
+/// The first round on base-field eq, a and b, as in `PlainSumcheck` but on `LANES` pairs at a
+/// time.
+fn eval_cubic_packed<B: SmallField>(polys: [&DensePolynomial<B>; 3], min_len: usize) -> [B; 4] {
+    let [eq, a, b] = polys;
+    let n = 1 << (eq.num_vars() - 1);
+    let blocks = n.min(eq.len().max(a.len()).max(b.len())).div_ceil(LANES);
+    let task = (min_len / LANES).max(TASK_BLOCKS);
+    (0..blocks.div_ceil(task))
+        .into_par_iter()
+        .map(|t| eval_blocks(polys, n, t * task..blocks.min((t + 1) * task)))
+        .reduce_with(|x, y| [0, 1, 2, 3].map(|k| x[k].merge(y[k])))
+        .unwrap_or([PackedSum::ZERO; 4])
+        .map(PackedSum::sum)
+}
+
+/// The packed sums over `blocks`, compiled for the instruction set picked at startup so that the
+/// lanes land in vector registers.
+fn eval_blocks<B: SmallField>(
+    polys: [&DensePolynomial<B>; 3],
+    n: usize,
+    blocks: Range<usize>,
+) -> [PackedSum<B>; 4] {
+    #[cfg(target_arch = "x86_64")]
+    {
+        #[target_feature(enable = "avx2,bmi2")]
+        unsafe fn avx2<B: SmallField>(
+            polys: [&DensePolynomial<B>; 3],
+            n: usize,
+            blocks: Range<usize>,
+        ) -> [PackedSum<B>; 4] {
+            eval_blocks_portable(polys, n, blocks)
+        }
+
+        #[target_feature(enable = "avx512f,avx512vl,avx512dq,bmi2")]
+        unsafe fn avx512<B: SmallField>(
+            polys: [&DensePolynomial<B>; 3],
+            n: usize,
+            blocks: Range<usize>,
+        ) -> [PackedSum<B>; 4] {
+            eval_blocks_portable(polys, n, blocks)
+        }
+
+        // `Isa::is_supported` does not cover every feature enabled above, so each one is
+        // checked here.
+        let avx2_features = || is_x86_feature_detected!("avx2") && is_x86_feature_detected!("bmi2");
+        match Isa::active() {
+            Isa::Avx512Ifma
+                if is_x86_feature_detected!("avx512f")
+                    && is_x86_feature_detected!("avx512vl")
+                    && is_x86_feature_detected!("avx512dq")
+                    && is_x86_feature_detected!("bmi2") =>
+            {
+                // SAFETY: the CPU supports every feature `avx512` is compiled with.
+                return unsafe { avx512(polys, n, blocks) };
+            }
+            Isa::Avx512Ifma | Isa::Avx2 if avx2_features() => {
+                // SAFETY: the CPU supports every feature `avx2` is compiled with.
+                return unsafe { avx2(polys, n, blocks) };
+            }
+            _ => {}
+        }
+    }
+    eval_blocks_portable(polys, n, blocks)
+}
+
+/// `eval_blocks` for the target features of its caller.
+#[inline(always)]
+fn eval_blocks_portable<B: SmallField>(
+    [eq, a, b]: [&DensePolynomial<B>; 3],
+    n: usize,
+    blocks: Range<usize>,
+) -> [PackedSum<B>; 4] {
+    let mut evals = [PackedSum::ZERO; 4];
+    for block in blocks {
+        let low = block * LANES;
+        let [(eq_low, eq_high), (a_low, a_high), (b_low, b_high)] =
+            [eq, a, b].map(|p| (load(p, low, n), load(p, n + low, 2 * n)));
+
+        evals[0].mul_add(eq_low * a_low, b_low);
+        evals[1].mul_add(eq_high * a_high, b_high);
+
+        let eq_m = eq_high - eq_low;
+        let a_m = a_high - a_low;
+        let b_m = b_high - b_low;
+
+        let eq_2 = eq_high + eq_m;
+        let a_2 = a_high + a_m;
+        let b_2 = b_high + b_m;
+        evals[2].mul_add(eq_2 * a_2, b_2);
+        evals[3].mul_add((eq_2 + eq_m) * (a_2 + a_m), b_2 + b_m);
+    }
+    evals
+}
+
+/// The `LANES` entries of `poly` from `start`, with those at `end` or past it zero.
+#[inline(always)]
+fn load<B: SmallField>(poly: &DensePolynomial<B>, start: usize, end: usize) -> PackedFp<B> {
+    match poly.Z.get(start..start + LANES) {
+        Some(words) if start + LANES <= end => PackedFp::from_slice(words),
+        _ => PackedFp::from_fn(|k| match start + k < end {
+            true => poly.get(start + k),
+            false => B::zero(),
+        }),
+    }
+}
+
+/// low + r * (high - low) into the extension, with the slope kept in the base field. Stored
+/// entries stay stored and the zero tail stays implicit.
+fn lift_bound<B: PrimeField, E: MulBase<BasePrimeField = B>>(
+    poly: &DensePolynomial<B>,
+    r: &E,
+    min_len: usize,
+) -> Vec<E> {
+    let n = 1 << (poly.num_vars() - 1);
+    (0..n.min(poly.len()))
+        .into_par_iter()
+        .with_min_len(min_len)
+        .map(|i| {
+            let low = poly.Z[i];
+            E::from_base_prime_field(low) + r.mul_base(&(poly.get(n + i) - low))
+        })
+        .collect()
+}
+
+impl<B, E> CubicSumcheck<E> for SmallFieldSumcheck<B, E>
+where
+    B: SmallField,
+    E: LazySum + MulBase<BasePrimeField = B>,
+{
+    fn new(eq: Vec<E>, a: Vec<E>, b: Vec<E>) -> Self {
+        SmallFieldSumcheck {
+            state: State::Ext(PlainSumcheck::new(eq, a, b)),
+        }
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        let state = match self.state {
+            State::Base(sumcheck) => State::Base(sumcheck.with_options(options)),
+            State::Ext(sumcheck) => State::Ext(sumcheck.with_options(options)),
+        };
+        SmallFieldSumcheck { state }
+    }
+
+    fn eval_cubic_top(&mut self) -> (E, E, E, E) {
+        match &mut self.state {
+            State::Base(sumcheck) => {
+                let n = 1 << (sumcheck.polys()[0].num_vars() - 1);
+                let min_len = sumcheck.options().strategy.execution(n).min_len(n);
+                let evals = match Isa::active() {
+                    // Lanes outside vector registers lose to the scalar round's lazy sums.
+                    Isa::Scalar => {
+                        let (e0, e1, e2, e3) = sumcheck.eval_cubic_top();
+                        [e0, e1, e2, e3]
+                    }
+                    _ => eval_cubic_packed(sumcheck.polys(), min_len),
+                };
+                let [e0, e1, e2, e3] = evals.map(E::from_base_prime_field);
+                (e0, e1, e2, e3)
+            }
+            State::Ext(sumcheck) => sumcheck.eval_cubic_top(),
+        }
+    }
+
+    fn bind_top(&mut self, r: &E) {
+        match &mut self.state {
+            State::Base(sumcheck) => {
+                let [eq, a, b] = sumcheck.polys();
+                let options = sumcheck.options();
+                let n = 1 << (eq.num_vars() - 1);
+                let min_len = options.strategy.execution(n).min_len(n);
+                let (eq, a, b) = (
+                    lift_bound(eq, r, min_len),
+                    lift_bound(a, r, min_len),
+                    lift_bound(b, r, min_len),
+                );
+                self.state = State::Ext(PlainSumcheck::new(eq, a, b).with_options(options));
+            }
+            State::Ext(sumcheck) => sumcheck.bind_top(r),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use crate::sumcheck::cubic_claim;
+
+    fn base_start_matches_extension<B, E>()
+    where
+        B: SmallField,
+        E: LazySum + MulBase<BasePrimeField = B>,
+    {
+        let log_size = 6;
+        let evals = rand_vec::<B>(4 << log_size);
+        let [eq, a, b] = [0, 1, 2].map(|k| evals[k << log_size..(k + 1) << log_size].to_vec());
+        let lift = |v: &[B]| -> Vec<E> { v.iter().map(|x| E::from_base_prime_field(*x)).collect() };
+        let claim = cubic_claim(&lift(&eq), &lift(&a), &lift(&b));
+
+        let mut prover = SmallFieldSumcheck::<B, E>::from_base(eq.clone(), a.clone(), b.clone());
+        assert!(prover.in_base_field());
+        let proof = prover.sumcheck_top(log_size);
+        assert!(!prover.in_base_field());
+
+        let reference = PlainSumcheck::new(lift(&eq), lift(&a), lift(&b)).sumcheck_top(log_size);
+        assert_eq!(proof, reference);
+        assert!(proof.verify(&claim).is_ok());
+
+        // Every challenge, the first included, leaves the base field.
+        for r in &proof.rs {
+            let coords: Vec<B> = r.to_base_prime_field_elements().collect();
+            assert!(coords[1..].iter().any(|c| !c.is_zero()));
+        }
+
+        // A zero tail left implicit proves the same as one written out.
+        let pad = |v: &[B]| {
+            let mut v = v.to_vec();
+            v.resize(1 << log_size, B::zero());
+            v
+        };
+        let (short_a, short_b) = (&a[..40], &b[..23]);
+        let ragged =
+            SmallFieldSumcheck::<B, E>::from_base(eq.clone(), short_a.to_vec(), short_b.to_vec())
+                .sumcheck_top(log_size);
+        let padded = SmallFieldSumcheck::<B, E>::from_base(eq, pad(short_a), pad(short_b))
+            .sumcheck_top(log_size);
+        assert_eq!(ragged, padded);
+
+        // Fewer pairs than lanes.
+        for num_vars in [1, 3] {
+            let [eq, a, b] = [0, 1, 2].map(|k| evals[k..k + (1 << num_vars)].to_vec());
+            let mut packed =
+                SmallFieldSumcheck::<B, E>::from_base(eq.clone(), a.clone(), b[1..].to_vec());
+            let mut lifted = PlainSumcheck::new(lift(&eq), lift(&a), lift(&b[1..]));
+            assert_eq!(
+                packed.eval_cubic_top(),
+                lifted.eval_cubic_top(),
+                "{num_vars}"
+            );
+        }
+    }
+
+    #[test]
+    fn small_field_sumcheck() {
+        base_start_matches_extension::<Goldilocks, GoldilocksExt2>();
+        base_start_matches_extension::<Goldilocks, GoldilocksExt4>();
+        base_start_matches_extension::<M31, QM31>();
+    }
+}
@@ -0,0 +1,206 @@
+use crate::field::lazy::LazySum;
+use crate::poly::plain::eq_evals;
+use crate::poly::sparse::SparsePolynomial;
+use crate::poly::split::SplitTables;
+use crate::poly::structured::EqPolynomial;
+use crate::sumcheck::plain::{accumulate_pair, PlainSumcheck};
+use crate::sumcheck::{CubicSumcheck, ProverOptions};
+use ark_bn254::Fr;
+use rayon::prelude::*;
+
+/// The density of a or b from which `SparseSumcheck` switches to the dense prover.
+pub const DEFAULT_DENSITY_THRESHOLD: f64 = 0.25;
+
+enum State<F: LazySum> {
+    Sparse {
+        eq: SplitTables<F>,
+        a: SparsePolynomial<F>,
+        b: SparsePolynomial<F>,
+    },
+    Dense(PlainSumcheck<F>),
+}
+
+/// Sumcheck of sum(eq * a * b) for a and b mostly zero. Started with `from_sparse`, a round
+/// touches only the pairs where both a and b have a non-zero side, and eq is kept as the tables of
+/// the two halves of its point, O(2^(m/2)) to build once and folded on bind.
+/// Once a or b fills `density_threshold` of the remaining hypercube, all three are written out
+/// and `PlainSumcheck` takes over. The proof is that of `PlainSumcheck` on the dense inputs.
+pub struct SparseSumcheck<F: LazySum = Fr> {
+    state: State<F>,
+    options: ProverOptions,
+    density_threshold: f64,
+}
+
+impl<F: LazySum> SparseSumcheck<F> {
+    pub fn from_sparse(
+        eq: &EqPolynomial<F>,
+        a: SparsePolynomial<F>,
+        b: SparsePolynomial<F>,
+    ) -> Self {
+        let num_vars = eq.point().len();
+        assert!(
+            a.num_vars() == num_vars && b.num_vars() == num_vars,
+            "eq, a and b must have the same number of variables (got {num_vars}, {}, {})",
+            a.num_vars(),
+            b.num_vars()
+        );
+        let (high, low) = eq.point().split_at(num_vars / 2);
+        let eq = SplitTables::new(eq_evals(high), eq_evals(low));
+        let mut sumcheck = SparseSumcheck {
+            state: State::Sparse { eq, a, b },
+            options: ProverOptions::default(),
+            density_threshold: DEFAULT_DENSITY_THRESHOLD,
+        };
+        sumcheck.densify_if_dense();
+        sumcheck
+    }
+
+    /// Switches to the dense prover once a or b reaches `density` of the remaining hypercube.
+    pub fn with_density_threshold(mut self, density: f64) -> Self {
+        self.density_threshold = density;
+        self.densify_if_dense();
+        self
+    }
+
+    /// Whether a and b are still sparse.
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.state, State::Sparse { .. })
+    }
+
+    fn densify_if_dense(&mut self) {
+        let State::Sparse { eq, a, b } = &self.state else {
+            return;
+        };
+        if a.density().max(b.density()) < self.density_threshold {
+            return;
+        }
+        let (a, b) = (a.to_dense().Z, b.to_dense().Z);
+        let dense = PlainSumcheck::new(eq.to_dense().Z, a, b).with_options(self.options);
+        self.state = State::Dense(dense);
+    }
+}
+
+impl<F: LazySum> CubicSumcheck<F> for SparseSumcheck<F> {
+    /// Starts dense; use `from_sparse` to start sparse.
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        SparseSumcheck {
+            state: State::Dense(PlainSumcheck::new(eq, a, b)),
+            options: ProverOptions::default(),
+            density_threshold: DEFAULT_DENSITY_THRESHOLD,
+        }
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        let state = match self.state {
+            State::Dense(sumcheck) => State::Dense(sumcheck.with_options(options)),
+            sparse => sparse,
+        };
+        SparseSumcheck {
+            state,
+            options,
+            ..self
+        }
+    }
+
+    fn eval_cubic_top(&mut self) -> (F, F, F, F) {
+        let (eq, a, b) = match &mut self.state {
+            State::Sparse { eq, a, b } => (eq, a, b),
+            State::Dense(sumcheck) => return sumcheck.eval_cubic_top(),
+        };
+        let half = 1 << (a.num_vars() - 1);
+        let pairs = joined_pairs(&a.top_pairs(), &b.top_pairs());
+
+        let evals = pairs
+            .par_iter()
+            .fold(
+                || [F::acc_zero(); 4],
+                |mut evals, (x, a_pair, b_pair)| {
+                    let eq_pair = (eq.get(*x), eq.get(x + half));
+                    accumulate_pair::<F>(&mut evals, eq_pair, *a_pair, *b_pair);
+                    evals
+                },
+            )
+            .reduce_with(|a, b| [0, 1, 2, 3].map(|k| F::merge(a[k], b[k])))
+            .unwrap_or([F::acc_zero(); 4]);
+
+        let [eval_0, eval_1, eval_2, eval_3] = evals.map(F::reduce);
+        (eval_0, eval_1, eval_2, eval_3)
+    }
+
+    fn bind_top(&mut self, r: &F) {
+        match &mut self.state {
+            State::Sparse { eq, a, b } => {
+                eq.bind_top(r);
+                a.bind_top(r);
+                b.bind_top(r);
+                self.densify_if_dense();
+            }
+            State::Dense(sumcheck) => sumcheck.bind_top(r),
+        }
+    }
+}
+
+/// The top-variable pairs of a and b at the indices where both have one.
+#[allow(clippy::type_complexity)]
+fn joined_pairs<F: Copy>(a: &[(usize, F, F)], b: &[(usize, F, F)]) -> Vec<(usize, (F, F), (F, F))> {
+    let mut joined = Vec::with_capacity(a.len().min(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                joined.push((a[i].0, (a[i].1, a[i].2), (b[j].1, b[j].2)));
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sumcheck::cubic_claim;
+    use ark_std::rand::Rng;
+    use ark_std::{test_rng, UniformRand};
+
+    fn random_sparse(num_vars: usize, nnz: usize, rng: &mut impl Rng) -> SparsePolynomial<Fr> {
+        let mut indices: Vec<usize> = (0..nnz).map(|_| rng.gen_range(0..1 << num_vars)).collect();
+        indices.sort();
+        indices.dedup();
+        SparsePolynomial::new(
+            num_vars,
+            indices.into_iter().map(|i| (i, Fr::rand(rng))).collect(),
+        )
+    }
+
+    #[test]
+    fn sparse_sumcheck_matches_plain() {
+        let mut rng = test_rng();
+        let num_vars = 12;
+        let point: Vec<Fr> = (0..num_vars).map(|_| Fr::rand(&mut rng)).collect();
+        let eq = EqPolynomial::new(point.clone());
+        let a = random_sparse(num_vars, 64, &mut rng);
+        let b = random_sparse(num_vars, 256, &mut rng);
+        let dense = (eq_evals(&point), a.to_dense().Z, b.to_dense().Z);
+        let claim = cubic_claim(&dense.0, &dense.1, &dense.2);
+        let reference = PlainSumcheck::new(dense.0, dense.1, dense.2).sumcheck_top(num_vars);
+
+        for threshold in [DEFAULT_DENSITY_THRESHOLD, 0.1, f64::INFINITY] {
+            let mut prover = SparseSumcheck::from_sparse(&eq, a.clone(), b.clone())
+                .with_density_threshold(threshold);
+            assert!(prover.is_sparse());
+            let proof = prover.sumcheck_top(num_vars);
+            assert_eq!(proof, reference, "{threshold}");
+            assert_eq!(prover.is_sparse(), threshold == f64::INFINITY);
+            proof.verify(&claim).unwrap();
+        }
+
+        // Dense enough from the start.
+        let prover =
+            SparseSumcheck::from_sparse(&eq, a.clone(), b.clone()).with_density_threshold(0.01);
+        assert!(!prover.is_sparse());
+    }
+}
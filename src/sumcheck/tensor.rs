@@ -0,0 +1,155 @@
+use crate::field::lazy::LazySum;
+use crate::poly::plain::DensePolynomial;
+use crate::poly::split::SplitTables;
+use crate::poly::structured::StructuredPolynomial;
+use crate::poly::tensor::TensorPolynomial;
+use crate::sumcheck::plain::{accumulate_pair, half_len};
+use crate::sumcheck::{CubicSumcheck, ProverOptions};
+use ark_bn254::Fr;
+use rayon::prelude::*;
+
+/// Sumcheck of sum(w * a * b) with w a `TensorPolynomial` that is never written out: rounds read
+/// w through split tables built once from its factors, and binding folds them. The proof is that
+/// of `PlainSumcheck` on the expanded w, and the verifier evaluates w itself through
+/// `Factor::Public`.
+pub struct TensorSumcheck<F: LazySum = Fr> {
+    w: SplitTables<F>,
+    a: DensePolynomial<F>,
+    b: DensePolynomial<F>,
+    options: ProverOptions,
+}
+
+impl<F: LazySum> TensorSumcheck<F> {
+    /// Over the variables of the longest of w, a and b. A w over fewer is proved as
+    /// `w.padded(num_vars)`, which is then the polynomial the verifier evaluates.
+    pub fn from_tensor(w: TensorPolynomial<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        let len = (1 << w.num_vars()).max(a.len()).max(b.len());
+        let num_vars = len.next_power_of_two().ilog2() as usize;
+        let w = w.padded(num_vars);
+        TensorSumcheck {
+            w: w.to_split_tables(),
+            a: DensePolynomial::new_padded(a, num_vars),
+            b: DensePolynomial::new_padded(b, num_vars),
+            options: ProverOptions::default(),
+        }
+    }
+}
+
+impl<F: LazySum> CubicSumcheck<F> for TensorSumcheck<F> {
+    /// eq as a tensor of one factor.
+    fn new(eq: Vec<F>, a: Vec<F>, b: Vec<F>) -> Self {
+        TensorSumcheck::from_tensor(TensorPolynomial::new(vec![eq]), a, b)
+    }
+
+    fn with_options(self, options: ProverOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    fn eval_cubic_top(&mut self) -> (F, F, F, F) {
+        let n = half_len(&self.a);
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        let live = n.min(self.a.len().max(self.b.len()));
+        let (w, a, b) = (&self.w, &self.a, &self.b);
+
+        let evals = (0..live)
+            .into_par_iter()
+            .with_min_len(min_len)
+            .fold(
+                || [F::acc_zero(); 4],
+                |mut evals, i| {
+                    let (low, high) = (i, n + i);
+                    accumulate_pair::<F>(
+                        &mut evals,
+                        (w.get(low), w.get(high)),
+                        (a.get(low), a.get(high)),
+                        (b.get(low), b.get(high)),
+                    );
+                    evals
+                },
+            )
+            .reduce_with(|a, b| [0, 1, 2, 3].map(|k| F::merge(a[k], b[k])))
+            .unwrap_or([F::acc_zero(); 4]);
+
+        let [eval_0, eval_1, eval_2, eval_3] = evals.map(F::reduce);
+        (eval_0, eval_1, eval_2, eval_3)
+    }
+
+    fn bind_top(&mut self, r: &F) {
+        let n = half_len(&self.a);
+        let min_len = self.options.strategy.execution(n).min_len(n);
+        self.w.bind_top(r);
+        self.a.bound_poly_var_top_min_len(r, min_len);
+        self.b.bound_poly_var_top_min_len(r, min_len);
+
+        self.options.shrink.apply(&mut self.a.Z);
+        self.options.shrink.apply(&mut self.b.Z);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::plain::rand_vec;
+    use crate::sumcheck::plain::PlainSumcheck;
+    use crate::sumcheck::{cubic_claim, Factor};
+
+    #[test]
+    fn tensor_sumcheck_matches_plain() {
+        let evals = rand_vec::<Fr>(1 << 11);
+        let factors = [4, 8, 2, 16].map(|n: usize| evals[n..2 * n].to_vec());
+        let tensors = [
+            TensorPolynomial::new(factors.to_vec()),
+            TensorPolynomial::eq(&evals[100..110]),
+        ];
+        for w in tensors {
+            let num_vars = w.num_vars();
+            let a = evals[..1 << num_vars].to_vec();
+            let b = evals[3..3 + (1 << num_vars)].to_vec();
+            let claim = cubic_claim(&w.evals().Z, &a, &b);
+
+            let proof =
+                TensorSumcheck::from_tensor(w.clone(), a.clone(), b.clone()).sumcheck_top(num_vars);
+            let reference =
+                PlainSumcheck::new(w.evals().Z, a.clone(), b.clone()).sumcheck_top(num_vars);
+            assert_eq!(proof, reference);
+
+            let (a, b) = (DensePolynomial::new(a), DensePolynomial::new(b));
+            let factors = [
+                Factor::Public(&w),
+                Factor::Claimed(a.evaluate(&proof.rs)),
+                Factor::Claimed(b.evaluate(&proof.rs)),
+            ];
+            assert_eq!(proof.verify_factors(&claim, factors), Ok(()));
+        }
+    }
+
+    #[test]
+    fn tensor_sumcheck_pads_to_the_longest() {
+        let evals = rand_vec::<Fr>(64);
+        // w over 3 variables against a over 5 and b over 6, and everything over none.
+        let cases = [
+            (vec![evals[..2].to_vec(), evals[2..6].to_vec()], 27, 40),
+            (vec![evals[..1].to_vec()], 1, 1),
+        ];
+        for (factors, a_len, b_len) in cases {
+            let w = TensorPolynomial::new(factors);
+            let (a, b) = (evals[..a_len].to_vec(), evals[8..8 + b_len].to_vec());
+            let num_vars = b_len.next_power_of_two().ilog2() as usize;
+            let padded = w.clone().padded(num_vars);
+            assert_eq!(padded.num_vars(), num_vars);
+            let claim = cubic_claim(&padded.evals().Z, &a, &b);
+
+            let proof = TensorSumcheck::from_tensor(w, a.clone(), b.clone()).sumcheck_top(num_vars);
+            let reference =
+                PlainSumcheck::new(padded.evals().Z, a.clone(), b.clone()).sumcheck_top(num_vars);
+            assert_eq!(proof, reference);
+
+            let factors = [
+                Factor::Public(&padded),
+                Factor::Claimed(DensePolynomial::new_padded(a, num_vars).evaluate(&proof.rs)),
+                Factor::Claimed(DensePolynomial::new_padded(b, num_vars).evaluate(&proof.rs)),
+            ];
+            assert_eq!(proof.verify_factors(&claim, factors), Ok(()));
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use crate::machine::MachineInfo;
+use crate::sumcheck::{Execution, RoundStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Overrides the default profile location.
+pub const PROFILE_ENV: &str = "SUMCHECK_SIMD_PROFILE";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    /// `SIMDPolynomial::bound_poly_var_top_par`, also used by `SIMDSumcheck::bind_top`.
+    Bind,
+    /// `SIMDSumcheck::eval_cubic_top`.
+    Eval,
+}
+
+impl Kernel {
+    /// The fixed formulas used when there is no profile: non-zero, with several chunks per thread
+    /// for better work-stealing, capped at 512.
+    pub fn heuristic_chunk_size(self, n: usize, threads: usize) -> usize {
+        let chunks_per_thread = match self {
+            Kernel::Bind => 16,
+            Kernel::Eval => 32,
+        };
+        std::cmp::min(n / threads / chunks_per_thread + 2, 512)
+    }
+}
+
+/// Tuned parameters of one kernel at one thread count.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelTuning {
+    pub threads: usize,
+    /// Half-lengths below this run as a single chunk on the calling thread.
+    pub sequential_below: usize,
+    /// Best chunk size keyed by log2 of the half-length.
+    pub chunk_sizes: BTreeMap<u32, usize>,
+}
+
+impl KernelTuning {
+    fn chunk_size(&self, n: usize) -> Option<usize> {
+        if n < self.sequential_below {
+            return Some(n.max(1));
+        }
+        self.chunk_sizes.get(&n.max(1).ilog2()).copied()
+    }
+}
+
+/// Chunk sizes found by `sumcheck tune` on one machine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimdProfile {
+    pub machine: MachineInfo,
+    pub bind: Vec<KernelTuning>,
+    pub eval: Vec<KernelTuning>,
+}
+
+impl SimdProfile {
+    /// Chunk size for `kernel` over a half-length of `n` with `threads` threads, or `None` if the
+    /// profile has no entry for that thread count and size class.
+    pub fn chunk_size(&self, kernel: Kernel, n: usize, threads: usize) -> Option<usize> {
+        let tunings = match kernel {
+            Kernel::Bind => &self.bind,
+            Kernel::Eval => &self.eval,
+        };
+        tunings
+            .iter()
+            .find(|t| t.threads == threads)
+            .and_then(|t| t.chunk_size(n))
+    }
+
+    /// `$SUMCHECK_SIMD_PROFILE` unless empty, or `simd-profile.json` under the user config
+    /// directory.
+    pub fn default_path() -> PathBuf {
+        if let Some(path) = std::env::var_os(PROFILE_ENV).filter(|path| !path.is_empty()) {
+            return path.into();
+        }
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .unwrap_or_default();
+        config.join("poly-bind-bench").join("simd-profile.json")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let profile = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(profile)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        let file = std::fs::File::create(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+static PROFILE: OnceLock<Option<SimdProfile>> = OnceLock::new();
+
+/// The profile at `SimdProfile::default_path`, or none when there is no file there or
+/// `$SUMCHECK_SIMD_PROFILE` is set but empty. A profile recorded on a different machine is an
+/// error.
+pub fn load_default_profile() -> Result<Option<SimdProfile>, Box<dyn Error>> {
+    if std::env::var_os(PROFILE_ENV).is_some_and(|path| path.is_empty()) {
+        return Ok(None);
+    }
+    let path = SimdProfile::default_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let profile = SimdProfile::load(&path)?;
+    if profile.machine != MachineInfo::current() {
+        return Err(format!("{}: recorded on a different machine", path.display()).into());
+    }
+    Ok(Some(profile))
+}
+
+/// Makes `profile` the one the SIMD backend uses, in place of loading the default one. Fails once
+/// the profile has been set or used.
+pub fn set_profile(profile: Option<SimdProfile>) -> Result<(), Box<dyn Error>> {
+    PROFILE
+        .set(profile)
+        .map_err(|_| "the SIMD profile is already in use".into())
+}
+
+/// The profile given to `set_profile`, or else `load_default_profile`'s on first use, with an
+/// error meaning no profile. Nothing is printed here; the binary reports a profile it ignores.
+/// Unit tests never load one, so they do not depend on the developer's environment; integration
+/// tests and benches can set an empty `$SUMCHECK_SIMD_PROFILE` or call `set_profile(None)`.
+pub fn profile() -> Option<&'static SimdProfile> {
+    PROFILE
+        .get_or_init(|| {
+            if cfg!(test) {
+                return None;
+            }
+            load_default_profile().ok().flatten()
+        })
+        .as_ref()
+}
+
+/// Chunk size for `kernel` over a half-length of `n` in the current rayon pool: the profile's
+/// choice when it has one, the heuristic otherwise.
+pub fn chunk_size(kernel: Kernel, n: usize) -> usize {
+    let threads = rayon::current_num_threads();
+    profile()
+        .and_then(|p| p.chunk_size(kernel, n, threads))
+        .unwrap_or_else(|| kernel.heuristic_chunk_size(n, threads))
+}
+
+/// How a SIMD prover round over a half-length of `n` runs. A profile that covers `n` at this
+/// thread count decides, since it was measured; `strategy` decides the sizes it does not cover,
+/// with the heuristic chunk size for its parallel rounds.
+pub fn round_chunking(
+    profile: Option<&SimdProfile>,
+    strategy: &RoundStrategy,
+    kernel: Kernel,
+    n: usize,
+    threads: usize,
+) -> (Execution, usize) {
+    if let Some(chunk_size) = profile.and_then(|p| p.chunk_size(kernel, n, threads)) {
+        let execution = if chunk_size >= n {
+            Execution::Sequential
+        } else {
+            Execution::Parallel
+        };
+        return (execution, chunk_size);
+    }
+    match strategy.execution(n) {
+        Execution::Parallel => (Execution::Parallel, kernel.heuristic_chunk_size(n, threads)),
+        execution => (execution, execution.min_len(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_lookup_falls_back_per_size_class() {
+        let profile = SimdProfile {
+            machine: MachineInfo::current(),
+            bind: vec![KernelTuning {
+                threads: 4,
+                sequential_below: 1 << 8,
+                chunk_sizes: [(10, 64), (11, 128)].into_iter().collect(),
+            }],
+            eval: vec![],
+        };
+        assert_eq!(profile.chunk_size(Kernel::Bind, 100, 4), Some(100));
+        assert_eq!(profile.chunk_size(Kernel::Bind, 1 << 10, 4), Some(64));
+        assert_eq!(
+            profile.chunk_size(Kernel::Bind, (1 << 12) - 1, 4),
+            Some(128)
+        );
+        assert_eq!(profile.chunk_size(Kernel::Bind, 1 << 12, 4), None);
+        assert_eq!(profile.chunk_size(Kernel::Bind, 1 << 10, 8), None);
+        assert_eq!(profile.chunk_size(Kernel::Eval, 1 << 10, 4), None);
+
+        // The profile's sizes win over the strategy's thresholds; the rest follow the strategy.
+        let strategy = RoundStrategy::default();
+        let chunking = |n| round_chunking(Some(&profile), &strategy, Kernel::Bind, n, 4);
+        assert_eq!(chunking(1 << 10), (Execution::Parallel, 64));
+        assert_eq!(chunking(100), (Execution::Sequential, 100));
+        let reduced = |n| (Execution::Reduced, Execution::Reduced.min_len(n));
+        assert_eq!(chunking(1 << 12), reduced(1 << 12));
+        assert_eq!(
+            round_chunking(None, &strategy, Kernel::Bind, 1 << 10, 4),
+            reduced(1 << 10)
+        );
+
+        let path = std::env::temp_dir().join("simd_profile_roundtrip.json");
+        profile.save(&path).unwrap();
+        assert_eq!(SimdProfile::load(&path).unwrap(), profile);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,21 @@
+//! `TrackingAllocator` installed in a test binary of its own, so the unit tests run on the plain
+//! system allocator.
+
+use poly_bind_bench::memory::{current_bytes, is_tracking, peak_bytes, TrackingAllocator};
+
+#[global_allocator]
+static ALLOC: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn tracks_live_and_peak_bytes() {
+    // The test harness may still allocate on other threads, so only lower bounds are exact.
+    const SIZE: usize = 64 << 20;
+    let before = current_bytes();
+    let buffer = vec![1u8; SIZE];
+    assert!(is_tracking());
+    let during = current_bytes();
+    assert!(during >= before + SIZE);
+    assert!(peak_bytes() >= during);
+    drop(buffer);
+    assert!(current_bytes() + SIZE / 2 <= during);
+}